@@ -0,0 +1,60 @@
+const STANDARD: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encode `input` using the standard base64 alphabet, with padding.
+pub(crate) fn encode<T: AsRef<[u8]>>(input: T) -> String {
+    encode_with(input.as_ref(), STANDARD)
+}
+
+/// Encode `input` using the URL and filename safe base64 alphabet, with padding.
+pub(crate) fn encode_url<T: AsRef<[u8]>>(input: T) -> String {
+    encode_with(input.as_ref(), URL_SAFE)
+}
+
+fn encode_with(input: &[u8], alphabet: &[u8; 64]) -> String {
+    let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+
+    for chunk in input.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(alphabet[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_standard() {
+        assert_eq!(encode(""), "");
+        assert_eq!(encode("f"), "Zg==");
+        assert_eq!(encode("foobar"), "Zm9vYmFy");
+        assert_eq!(encode([0xfb, 0xff]), "+/8=");
+    }
+
+    #[test]
+    fn encode_url_safe() {
+        assert_eq!(encode_url(""), "");
+        assert_eq!(encode_url("f"), "Zg==");
+        assert_eq!(encode_url("fo"), "Zm8=");
+        assert_eq!(encode_url("foo"), "Zm9v");
+        assert_eq!(encode_url("foob"), "Zm9vYg==");
+        assert_eq!(encode_url("fooba"), "Zm9vYmE=");
+        assert_eq!(encode_url("foobar"), "Zm9vYmFy");
+        assert_eq!(encode_url([0xfb, 0xff]), "-_8=");
+    }
+}
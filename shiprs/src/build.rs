@@ -0,0 +1,131 @@
+use serde::Serialize;
+
+use shiprs_models::models::BuildCacheTypeEnum;
+
+use crate::Filter;
+
+/// Parameters used for the [Prune Build Cache API](crate::Docker::prune_build_cache)
+///
+/// ## Examples
+///
+/// ```rust
+/// use shiprs::build::{BuildPruneFilter, BuildPruneOption};
+///
+/// // Remove all the build cache unused for a day, keeping 10GB of it.
+/// let options = BuildPruneOption {
+///     all: true,
+///     keep_storage: Some(10 * 1024 * 1024 * 1024),
+///     filters: vec![BuildPruneFilter::Until("24h"), BuildPruneFilter::InUse(false)],
+/// };
+/// ```
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BuildPruneOption<T>
+where
+    T: AsRef<str>,
+{
+    /// Remove all types of build cache, not only the dangling one.
+    pub all: bool,
+
+    /// Amount of disk space in bytes to keep for cache.
+    #[serde(rename = "keep-storage")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_storage: Option<i64>,
+
+    /// Filters to process on the build cache records.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(serialize_with = "crate::serialize_filters")]
+    pub filters: Vec<BuildPruneFilter<T>>,
+}
+
+/// Filters available for the [Prune Build Cache API](crate::Docker::prune_build_cache).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildPruneFilter<T>
+where
+    T: AsRef<str>,
+{
+    /// Only prune the records unused since this duration or timestamp, e.g. `24h` or `2024-01-01T00:00:00`.
+    Until(T),
+    /// Only prune the record with this ID.
+    Id(T),
+    /// Only prune the children of the record with this ID.
+    Parent(T),
+    /// Only prune the records of this type.
+    Type(BuildCacheTypeEnum),
+    /// Only prune the records with this description.
+    Description(T),
+    /// Only prune the records which are in use, or not in use.
+    InUse(bool),
+    /// Only prune the shared records, or the non shared ones.
+    Shared(bool),
+    /// Only prune the private records, or the non private ones.
+    Private(bool),
+}
+
+impl<T> Filter for BuildPruneFilter<T>
+where
+    T: AsRef<str>,
+{
+    fn key(&self) -> &'static str {
+        match self {
+            BuildPruneFilter::Until(_) => "until",
+            BuildPruneFilter::Id(_) => "id",
+            BuildPruneFilter::Parent(_) => "parent",
+            BuildPruneFilter::Type(_) => "type",
+            BuildPruneFilter::Description(_) => "description",
+            BuildPruneFilter::InUse(_) => "inuse",
+            BuildPruneFilter::Shared(_) => "shared",
+            BuildPruneFilter::Private(_) => "private",
+        }
+    }
+
+    fn value(&self) -> String {
+        match self {
+            BuildPruneFilter::Until(value)
+            | BuildPruneFilter::Id(value)
+            | BuildPruneFilter::Parent(value)
+            | BuildPruneFilter::Description(value) => value.as_ref().to_string(),
+            BuildPruneFilter::Type(typ) => typ.to_string(),
+            BuildPruneFilter::InUse(value)
+            | BuildPruneFilter::Shared(value)
+            | BuildPruneFilter::Private(value) => value.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Result;
+    use crate::mock::{json_response, MockDaemon};
+
+    #[test]
+    fn prune_build_cache() -> Result<()> {
+        let daemon = MockDaemon::start([json_response(
+            200,
+            r#"{"CachesDeleted":["ndlpt0hhvkqcdfkputsk4cq9c"],"SpaceReclaimed":1024}"#,
+        )]);
+
+        let options = BuildPruneOption {
+            all: true,
+            keep_storage: Some(512),
+            filters: vec![
+                BuildPruneFilter::Until("24h"),
+                BuildPruneFilter::Type(BuildCacheTypeEnum::REGULAR),
+                BuildPruneFilter::Shared(false),
+            ],
+        };
+        let pruned = daemon.docker().prune_build_cache(Some(options))?;
+
+        assert_eq!(
+            daemon.request().line,
+            "POST /v1.44/build/prune?all=true&keep-storage=512&filters=%7B%22shared%22%3A%5B%22false%22%5D%2C%22type%22%3A%5B%22regular%22%5D%2C%22until%22%3A%5B%2224h%22%5D%7D HTTP/1.1"
+        );
+        assert_eq!(
+            pruned.caches_deleted,
+            Some(vec!["ndlpt0hhvkqcdfkputsk4cq9c".to_string()])
+        );
+        assert_eq!(pruned.space_reclaimed, Some(1024));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,366 @@
+use serde::Serialize;
+
+use shiprs_http::uri::PathTemplate;
+use shiprs_http::RequestBuilder;
+use shiprs_models::models::{self, ConfigSpec, IdResponse};
+
+use crate::docker::Docker;
+use crate::error::Result;
+use crate::Filter;
+
+/// Interface for interacting with a swarm config, a file such as `nginx.conf` mounted into the
+/// containers of the services using it.
+///
+/// # Example
+///
+/// ```no_run
+/// # use shiprs::error::Result;
+/// use shiprs::Docker;
+///
+/// # fn main() -> Result<()> {
+/// let docker = Docker::new()?;
+///
+/// let config = docker.configs().get("nginx.conf").inspect()?;
+///
+/// println!("last updated at {:?}", config.updated_at);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Config<'docker, T> {
+    docker: &'docker Docker,
+    id: T,
+}
+
+impl<'docker, T> Config<'docker, T>
+where
+    T: AsRef<str>,
+{
+    pub(crate) fn new(docker: &'docker Docker, id: T) -> Self {
+        Config { docker, id }
+    }
+
+    /// Inspect the config. Unlike the data of a secret, the data of a config is returned,
+    /// base64 encoded, in its spec.
+    /// This corresponds to the `GET /configs/(id)` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Config/operation/ConfigInspect) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let config = docker.configs().get("nginx.conf").inspect()?;
+    ///
+    /// let encoded = config.spec.and_then(|spec| spec.data).unwrap_or_default();
+    /// println!("nginx.conf: {}", encoded);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn inspect(&self) -> Result<models::Config> {
+        let url = PathTemplate::new("/configs/{}")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<(), ()>::get(&url).build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// Update the config.
+    /// This corresponds to the `POST /configs/(id)/update` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Config/operation/ConfigUpdate) for more information.
+    ///
+    /// # Description
+    /// The `version` of the config being updated must be given in the options, as returned by [`Config::inspect`].
+    /// Only the labels of a config can be updated, the other fields must be left unchanged.
+    /// The data returned by [`Config::inspect`] is already encoded, and is sent back as is.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::config::UpdateOption;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let config = docker.configs().get("nginx.conf");
+    ///
+    /// let current = config.inspect()?;
+    /// let mut spec = current.spec.unwrap_or_default();
+    /// spec.labels = Some([("com.example.proxy".to_string(), "edge".to_string())].into());
+    ///
+    /// let options = UpdateOption {
+    ///     version: current.version.and_then(|v| v.index).unwrap_or_default(),
+    /// };
+    /// config.update(spec, options)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn update(&self, spec: ConfigSpec, options: UpdateOption) -> Result<()> {
+        let url = PathTemplate::new("/configs/{}/update")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<UpdateOption, ConfigSpec>::post(&url)
+            .query(Some(options))
+            .body(spec)
+            .build()?;
+
+        self.docker.process_into_unit(request)
+    }
+
+    /// Remove the config.
+    /// This corresponds to the `DELETE /configs/(id)` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Config/operation/ConfigDelete) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// docker.configs().get("nginx.conf").remove()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remove(&self) -> Result<()> {
+        let url = PathTemplate::new("/configs/{}")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<(), ()>::delete(&url).build()?;
+
+        self.docker.process_into_unit(request)
+    }
+}
+
+/// Interface for interacting with the swarm configs.
+///
+/// # Example
+///
+/// ```no_run
+/// # use shiprs::error::Result;
+/// use shiprs::Docker;
+///
+/// # fn main() -> Result<()> {
+/// let docker = Docker::new()?;
+///
+/// for config in docker.configs().list::<String>(None)? {
+///     println!("{:?} created at {:?}", config.id, config.created_at);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Configs<'docker> {
+    docker: &'docker Docker,
+}
+
+impl<'docker> Configs<'docker> {
+    pub(crate) fn new(docker: &'docker Docker) -> Self {
+        Configs { docker }
+    }
+
+    /// Create a new config holding `data`.
+    /// This corresponds to the `POST /configs/create` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Config/operation/ConfigCreate) for more information.
+    ///
+    /// # Description
+    /// The data is base64 encoded into the `data` field of the spec, replacing its value.
+    /// Configs are not encrypted at rest, sensitive data should be stored in a
+    /// [secret](crate::secret::Secrets::create) instead.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs_models::models::ConfigSpec;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let spec = ConfigSpec {
+    ///     name: Some("nginx.conf".to_string()),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let data = std::fs::read("nginx.conf")?;
+    /// let config = docker.configs().create(spec, &data)?;
+    /// println!("{}", config.id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create(&self, mut spec: ConfigSpec, data: &[u8]) -> Result<IdResponse> {
+        spec.data = Some(crate::base64::encode(data));
+
+        let url = "/configs/create";
+        let request = RequestBuilder::<(), ConfigSpec>::post(url)
+            .body(spec)
+            .build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// List the configs.
+    /// This corresponds to the `GET /configs` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Config/operation/ConfigList) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::config::{ListFilter, ListOption};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let options = ListOption {
+    ///     filters: vec![ListFilter::Name("nginx")],
+    /// };
+    ///
+    /// let nginx_configs = docker.configs().list(Some(options))?;
+    /// println!("{} nginx configs", nginx_configs.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list<T>(&self, options: Option<ListOption<T>>) -> Result<Vec<models::Config>>
+    where
+        T: AsRef<str> + Serialize,
+    {
+        let url = "/configs";
+        let request = RequestBuilder::<ListOption<T>, ()>::get(url)
+            .query(options)
+            .build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// Get a config by id or name.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let config = docker.configs().get("nginx.conf");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get<T>(self, id: T) -> Config<'docker, T>
+    where
+        T: AsRef<str>,
+    {
+        Config::new(self.docker, id)
+    }
+}
+
+/// Parameters used for the [List Configs API](Configs::list)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListOption<T>
+where
+    T: AsRef<str>,
+{
+    /// Filters to process on the config list.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(serialize_with = "crate::serialize_filters")]
+    pub filters: Vec<ListFilter<T>>,
+}
+
+/// Filters available for the [List Configs API](Configs::list).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListFilter<T>
+where
+    T: AsRef<str>,
+{
+    /// Only list the config with this ID.
+    Id(T),
+    /// Only list the configs with this label, given as `key` or `key=value`.
+    Label(T),
+    /// Only list the configs whose name starts with this prefix.
+    Name(T),
+    /// Only list the config with exactly this name.
+    Names(T),
+}
+
+impl<T> Filter for ListFilter<T>
+where
+    T: AsRef<str>,
+{
+    fn key(&self) -> &'static str {
+        match self {
+            ListFilter::Id(_) => "id",
+            ListFilter::Label(_) => "label",
+            ListFilter::Name(_) => "name",
+            ListFilter::Names(_) => "names",
+        }
+    }
+
+    fn value(&self) -> String {
+        match self {
+            ListFilter::Id(value)
+            | ListFilter::Label(value)
+            | ListFilter::Name(value)
+            | ListFilter::Names(value) => value.as_ref().to_string(),
+        }
+    }
+}
+
+/// Options for the [`update`](Config::update) method.
+/// This struct corresponds to the param options of the `POST /configs/(id)/update` endpoint.
+/// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Config/operation/ConfigUpdate) for more information.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct UpdateOption {
+    /// The version number of the config object being updated.
+    /// This is required to avoid conflicting writes.
+    pub version: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{json_response, MockDaemon};
+
+    #[test]
+    fn create_config_encodes_data() -> Result<()> {
+        let daemon =
+            MockDaemon::start([json_response(201, r#"{"ID":"rjc0y4gvqv3o6zgsm3e6bmqpb"}"#)]);
+
+        let spec = ConfigSpec {
+            name: Some("nginx.conf".to_string()),
+            ..Default::default()
+        };
+        let config = daemon
+            .docker()
+            .configs()
+            .create(spec, b"worker_processes 4;\n")?;
+
+        let request = daemon.request();
+        assert_eq!(request.line, "POST /v1.44/configs/create HTTP/1.1");
+        assert_eq!(
+            request.body,
+            br#"{"Name":"nginx.conf","Data":"d29ya2VyX3Byb2Nlc3NlcyA0Owo="}"#
+        );
+        assert_eq!(config.id, "rjc0y4gvqv3o6zgsm3e6bmqpb");
+
+        Ok(())
+    }
+
+    #[test]
+    fn inspect_config_returns_data() -> Result<()> {
+        let daemon = MockDaemon::start([json_response(
+            200,
+            r#"{"ID":"rjc0y4gvqv3o6zgsm3e6bmqpb","Version":{"Index":11},"Spec":{"Name":"nginx.conf","Data":"d29ya2VyX3Byb2Nlc3NlcyA0Owo="}}"#,
+        )]);
+
+        let config = daemon.docker().configs().get("nginx.conf").inspect()?;
+
+        assert_eq!(
+            daemon.request().line,
+            "GET /v1.44/configs/nginx.conf HTTP/1.1"
+        );
+        let spec = config.spec.unwrap();
+        assert_eq!(spec.data.as_deref(), Some("d29ya2VyX3Byb2Nlc3NlcyA0Owo="));
+
+        Ok(())
+    }
+}
@@ -3,11 +3,13 @@ use std::hash::Hash;
 
 use serde::{Deserialize, Serialize};
 
+use shiprs_http::uri::PathTemplate;
 use shiprs_http::RequestBuilder;
 use shiprs_models::models::*;
 
 use crate::docker::Docker;
 use crate::error::Result;
+use crate::Filter;
 
 /// Interface for interacting with a container.
 ///
@@ -63,10 +65,12 @@ where
         &self,
         options: Option<ContainerInspectOption>,
     ) -> Result<ContainerInspectResponse> {
-        let url = format!("/containers/{}/json", self.id.as_ref());
-        let request = RequestBuilder::<ContainerInspectOption, ()>::get(&*url)
+        let url = PathTemplate::new("/containers/{}/json")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<ContainerInspectOption, ()>::get(&url)
             .query(options)
-            .build();
+            .build()?;
 
         self.docker.process_into_value(request)
     }
@@ -98,10 +102,12 @@ where
     where
         O: Serialize + Into<String>,
     {
-        let url = format!("/containers/{}/top", self.id.as_ref());
-        let request = RequestBuilder::<ContainerTopOption<O>, ()>::get(&*url)
+        let url = PathTemplate::new("/containers/{}/top")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<ContainerTopOption<O>, ()>::get(&url)
             .query(options)
-            .build();
+            .build()?;
 
         self.docker.process_into_value(request)
     }
@@ -127,8 +133,10 @@ where
     /// # Ok(())
     /// # }
     pub fn export(&self) -> Result<()> {
-        let url = format!("/containers/{}/export", self.id.as_ref());
-        let request = RequestBuilder::<(), ()>::get(&*url).build();
+        let url = PathTemplate::new("/containers/{}/export")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<(), ()>::get(&url).build()?;
 
         self.docker.process_into_unit(request)
     }
@@ -155,8 +163,10 @@ where
     /// # Ok(())
     /// # }
     pub fn changes(&self) -> Result<Vec<FilesystemChange>> {
-        let url = format!("/containers/{}/changes", self.id.as_ref());
-        let request = RequestBuilder::<(), ()>::get(&*url).build();
+        let url = PathTemplate::new("/containers/{}/changes")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<(), ()>::get(&url).build()?;
 
         self.docker.process_into_value(request)
     }
@@ -180,10 +190,12 @@ where
     /// # }
     /// ```
     pub fn remove(&self, options: Option<RemoveOption>) -> Result<()> {
-        let url = format!("/containers/{}", self.id.as_ref());
-        let request = RequestBuilder::<RemoveOption, ()>::delete(&*url)
+        let url = PathTemplate::new("/containers/{}")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<RemoveOption, ()>::delete(&url)
             .query(options)
-            .build();
+            .build()?;
 
         self.docker.process_into_unit(request)
     }
@@ -217,10 +229,12 @@ where
     /// # }
     /// ```
     pub fn resize(&self, options: Option<ResizeOption>) -> Result<()> {
-        let url = format!("/containers/{}/resize", self.id.as_ref());
-        let request = RequestBuilder::<ResizeOption, ()>::post(&*url)
+        let url = PathTemplate::new("/containers/{}/resize")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<ResizeOption, ()>::post(&url)
             .query(options)
-            .build();
+            .build()?;
 
         self.docker.process_into_unit(request)
     }
@@ -229,35 +243,52 @@ where
     /// This corresponds to the `POST /containers/(id)/start` endpoint.
     /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Container/operation/ContainerStart) for more information.
     ///
+    /// Returns [`StateChange::AlreadyInState`] if the container was already running.
+    /// With a [`RetryPolicy`](crate::docker::RetryPolicy), this is also returned when a first
+    /// attempt changed the state but its response was lost, and the retry found the container
+    /// already running.
+    ///
     /// # Example
     /// ```no_run
     /// # use shiprs::error::Result;
     /// use shiprs::Docker;
+    /// use shiprs::container::StateChange;
     ///
     /// # fn main() -> Result<()> {
     /// let docker = Docker::new().unwrap();
     ///
-    /// docker
+    /// let state = docker
     ///     .containers()
     ///     .get("insert container id here")
     ///     .start(None)?;
     ///
+    /// if state == StateChange::AlreadyInState {
+    ///     println!("The container was already running");
+    /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub fn start(&self, options: Option<StartOption>) -> Result<()> {
-        let url = format!("/containers/{}/start", self.id.as_ref());
-        let request = RequestBuilder::<StartOption, ()>::post(&*url)
+    pub fn start(&self, options: Option<StartOption>) -> Result<StateChange> {
+        let url = PathTemplate::new("/containers/{}/start")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<StartOption, ()>::post(&url)
             .query(options)
-            .build();
+            .idempotent()
+            .build()?;
 
-        self.docker.process_into_unit(request)
+        self.docker.process_into_state_change(request)
     }
 
     /// Stop a container.
     /// This corresponds to the `POST /containers/(id)/stop` endpoint.
     /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Container/operation/ContainerStop) for more information.
     ///
+    /// Returns [`StateChange::AlreadyInState`] if the container was already stopped.
+    /// With a [`RetryPolicy`](crate::docker::RetryPolicy), this is also returned when a first
+    /// attempt changed the state but its response was lost, and the retry found the container
+    /// already stopped.
+    ///
     /// # Example
     /// ```no_run
     /// # use shiprs::error::Result;
@@ -279,13 +310,16 @@ where
     /// # Ok(())
     /// # }
     /// ```
-    pub fn stop(&self, options: Option<StopOption>) -> Result<()> {
-        let url = format!("/containers/{}/stop", self.id.as_ref());
-        let request = RequestBuilder::<StopOption, ()>::post(&*url)
+    pub fn stop(&self, options: Option<StopOption>) -> Result<StateChange> {
+        let url = PathTemplate::new("/containers/{}/stop")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<StopOption, ()>::post(&url)
             .query(options)
-            .build();
+            .idempotent()
+            .build()?;
 
-        self.docker.process_into_unit(request)
+        self.docker.process_into_state_change(request)
     }
 
     /// Restart a container.
@@ -314,10 +348,12 @@ where
     /// # }
     /// ```
     pub fn restart(&self, options: Option<RestartOption>) -> Result<()> {
-        let url = format!("/containers/{}/restart", self.id.as_ref());
-        let request = RequestBuilder::<RestartOption, ()>::post(&*url)
+        let url = PathTemplate::new("/containers/{}/restart")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<RestartOption, ()>::post(&url)
             .query(options)
-            .build();
+            .build()?;
 
         self.docker.process_into_unit(request)
     }
@@ -343,10 +379,12 @@ where
     /// # }
     /// ```
     pub fn kill(&self, options: Option<KillOption>) -> Result<()> {
-        let url = format!("/containers/{}/kill", self.id.as_ref());
-        let request = RequestBuilder::<KillOption, ()>::post(&*url)
+        let url = PathTemplate::new("/containers/{}/kill")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<KillOption, ()>::post(&url)
             .query(options)
-            .build();
+            .build()?;
 
         self.docker.process_into_unit(request)
     }
@@ -380,10 +418,12 @@ where
     where
         C: Into<String> + Eq + Hash + Serialize,
     {
-        let url = format!("/containers/{}/update", self.id.as_ref());
-        let request = RequestBuilder::<(), UpdateConfig<C>>::post(&*url)
+        let url = PathTemplate::new("/containers/{}/update")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<(), UpdateConfig<C>>::post(&url)
             .body(config)
-            .build();
+            .build()?;
 
         self.docker.process_into_unit(request)
     }
@@ -416,10 +456,12 @@ where
     where
         O: Into<String> + Serialize,
     {
-        let url = format!("/containers/{}/rename", self.id.as_ref());
-        let request = RequestBuilder::<RenameOption<O>, ()>::post(&*url)
+        let url = PathTemplate::new("/containers/{}/rename")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<RenameOption<O>, ()>::post(&url)
             .query(Some(option))
-            .build();
+            .build()?;
 
         self.docker.process_into_unit(request)
     }
@@ -451,8 +493,10 @@ where
     /// # }
     /// ```
     pub fn pause(&self) -> Result<()> {
-        let url = format!("/containers/{}/pause", self.id.as_ref());
-        let request = RequestBuilder::<(), ()>::post(&*url).build();
+        let url = PathTemplate::new("/containers/{}/pause")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<(), ()>::post(&url).build()?;
 
         self.docker.process_into_unit(request)
     }
@@ -481,11 +525,69 @@ where
     /// # }
     /// ```
     pub fn unpause(&self) -> Result<()> {
-        let url = format!("/containers/{}/unpause", self.id.as_ref());
-        let request = RequestBuilder::<(), ()>::post(&*url).build();
+        let url = PathTemplate::new("/containers/{}/unpause")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<(), ()>::post(&url).build()?;
 
         self.docker.process_into_unit(request)
     }
+
+    /// Create a new image from the container.
+    /// This corresponds to the `POST /commit` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Image/operation/ImageCommit) for more information.
+    ///
+    /// # Description
+    /// Create a new image from the container's changes.
+    /// The `config` is merged with the container's configuration to produce the configuration of the new image.
+    ///
+    /// Returns the ID of the created image.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::container::CommitOption;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new().unwrap();
+    /// let options = CommitOption {
+    ///     repo: Some("my_image"),
+    ///     tag: Some("latest"),
+    ///     changes: vec!["ENV DEBUG=true", "CMD [\"/bin/sh\"]"],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let image = docker
+    ///     .containers()
+    ///     .get("insert container id here")
+    ///     .commit(options, None)?;
+    ///
+    /// println!("{}", image.id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn commit<O>(
+        &self,
+        options: CommitOption<O>,
+        config: Option<ContainerConfig>,
+    ) -> Result<IdResponse>
+    where
+        O: Into<String> + AsRef<str> + Serialize,
+    {
+        let url = "/commit";
+        let query = CommitQuery {
+            container: self.id.as_ref(),
+            options,
+        };
+        let mut request =
+            RequestBuilder::<CommitQuery<O>, ContainerConfig>::post(url).query(Some(query));
+        if let Some(config) = config {
+            request = request.body(config);
+        }
+
+        self.docker.process_into_value(request.build()?)
+    }
 }
 
 /// Interface for interacting with docker containers.
@@ -560,7 +662,7 @@ impl<'docker> Containers<'docker> {
         let request = RequestBuilder::<CreateOption<O>, CreateConfig<C>>::post(url)
             .query(options)
             .body(config)
-            .build();
+            .build()?;
 
         self.docker.process_into_value(request)
     }
@@ -589,7 +691,41 @@ impl<'docker> Containers<'docker> {
         let url = "/containers/json";
         let request = RequestBuilder::<ListOption<T>, ()>::get(url)
             .query(options)
-            .build();
+            .build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// Delete stopped containers.
+    /// This corresponds to the `POST /containers/prune` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Container/operation/ContainerPrune) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::container::{PruneFilter, PruneOption};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let options = PruneOption {
+    ///     filters: vec![PruneFilter::Until("24h"), PruneFilter::LabelNot("keep")],
+    /// };
+    ///
+    /// let pruned = docker.containers().prune(Some(options))?;
+    /// println!("Deleted: {:?}", pruned.containers_deleted);
+    /// println!("Reclaimed {:?} bytes", pruned.space_reclaimed);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn prune<T>(&self, options: Option<PruneOption<T>>) -> Result<ContainerPruneResponse>
+    where
+        T: AsRef<str> + Serialize,
+    {
+        let url = "/containers/prune";
+        let request = RequestBuilder::<PruneOption<T>, ()>::post(url)
+            .query(options)
+            .build()?;
 
         self.docker.process_into_value(request)
     }
@@ -617,6 +753,22 @@ impl<'docker> Containers<'docker> {
     }
 }
 
+/// Outcome of a request changing the state of a container, such as [`Container::start`] or [`Container::stop`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateChange {
+    /// The container was moved to the requested state.
+    Changed,
+    /// The container was already in the requested state, so nothing was done (`304 Not Modified`).
+    AlreadyInState,
+}
+
+impl StateChange {
+    /// Returns `true` if the state of the container actually changed.
+    pub fn is_changed(&self) -> bool {
+        *self == StateChange::Changed
+    }
+}
+
 /// Options for the `inspect` method.
 /// This struct corresponds to the param options of the `GET /containers/(id)/json` endpoint.
 /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Container/operation/ContainerInspect) for more information.
@@ -1112,3 +1264,224 @@ where
     /// The new name for the container.
     pub name: T,
 }
+
+/// Parameters used for the [Prune Container API](Containers::prune)
+///
+/// ## Examples
+///
+/// ```rust
+/// use shiprs::container::{PruneFilter, PruneOption};
+///
+/// // Prune the containers stopped for more than a day, except those labelled `keep`.
+/// let options = PruneOption {
+///     filters: vec![PruneFilter::Until("24h"), PruneFilter::LabelNot("keep")],
+/// };
+/// ```
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PruneOption<T>
+where
+    T: AsRef<str>,
+{
+    /// Filters to process on the prune list.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(serialize_with = "crate::serialize_filters")]
+    pub filters: Vec<PruneFilter<T>>,
+}
+
+/// Filters available for the [Prune Container API](Containers::prune).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PruneFilter<T>
+where
+    T: AsRef<str>,
+{
+    /// Only prune the containers created before this timestamp or duration, e.g. `10m` or `2024-01-01T00:00:00`.
+    Until(T),
+    /// Only prune the containers with this label, given as `key` or `key=value`.
+    Label(T),
+    /// Only prune the containers without this label, given as `key` or `key=value`.
+    LabelNot(T),
+}
+
+impl<T> Filter for PruneFilter<T>
+where
+    T: AsRef<str>,
+{
+    fn key(&self) -> &'static str {
+        match self {
+            PruneFilter::Until(_) => "until",
+            PruneFilter::Label(_) => "label",
+            PruneFilter::LabelNot(_) => "label!",
+        }
+    }
+
+    fn value(&self) -> String {
+        match self {
+            PruneFilter::Until(value)
+            | PruneFilter::Label(value)
+            | PruneFilter::LabelNot(value) => value.as_ref().to_string(),
+        }
+    }
+}
+
+/// Options for the [`commit`](Container::commit) method.
+/// This struct corresponds to the param options of the `POST /commit` endpoint.
+/// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Image/operation/ImageCommit) for more information.
+#[derive(Default, Serialize)]
+pub struct CommitOption<T>
+where
+    T: Into<String> + AsRef<str> + Serialize,
+{
+    /// Repository name for the created image.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo: Option<T>,
+
+    /// Tag name for the create image.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<T>,
+
+    /// Commit message.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<T>,
+
+    /// Author of the image (e.g., `John Hannibal Smith <hannibal@a-team.com>`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<T>,
+
+    /// Whether to pause the container before committing.
+    /// By default, the daemon pauses the container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pause: Option<bool>,
+
+    /// `Dockerfile` instructions to apply while committing, e.g. `ENV DEBUG=true` or `CMD ["/bin/sh"]`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(serialize_with = "serialize_changes")]
+    pub changes: Vec<T>,
+}
+
+#[derive(Serialize)]
+struct CommitQuery<'a, T>
+where
+    T: Into<String> + AsRef<str> + Serialize,
+{
+    container: &'a str,
+    #[serde(flatten)]
+    options: CommitOption<T>,
+}
+
+/// The daemon expects the `Dockerfile` instructions separated by new lines.
+fn serialize_changes<T, S>(changes: &[T], s: S) -> std::result::Result<S::Ok, S::Error>
+where
+    T: AsRef<str>,
+    S: serde::Serializer,
+{
+    let changes = changes
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<Vec<_>>()
+        .join("\n");
+    s.serialize_str(&changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{json_response, MockDaemon};
+
+    #[test]
+    fn prune_containers() -> Result<()> {
+        let daemon = MockDaemon::start([json_response(
+            200,
+            r#"{"ContainersDeleted":["4ca5d3a7e3f2","b9c2f5b7c1d0"],"SpaceReclaimed":4096}"#,
+        )]);
+
+        let options = PruneOption {
+            filters: vec![
+                PruneFilter::Until("24h"),
+                PruneFilter::Label("env=ci"),
+                PruneFilter::LabelNot("keep"),
+                PruneFilter::Label("janitor"),
+            ],
+        };
+        let pruned = daemon.docker().containers().prune(Some(options))?;
+
+        assert_eq!(
+            daemon.request().line,
+            "POST /v1.44/containers/prune?filters=%7B%22label%22%3A%5B%22env%3Dci%22%2C%22janitor%22%5D%2C%22label%21%22%3A%5B%22keep%22%5D%2C%22until%22%3A%5B%2224h%22%5D%7D HTTP/1.1"
+        );
+        assert_eq!(
+            pruned.containers_deleted,
+            Some(vec!["4ca5d3a7e3f2".to_string(), "b9c2f5b7c1d0".to_string()])
+        );
+        assert_eq!(pruned.space_reclaimed, Some(4096));
+
+        Ok(())
+    }
+
+    #[test]
+    fn prune_containers_without_filters() -> Result<()> {
+        let daemon = MockDaemon::start([json_response(
+            200,
+            r#"{"ContainersDeleted":null,"SpaceReclaimed":0}"#,
+        )]);
+
+        let pruned = daemon.docker().containers().prune::<&str>(None)?;
+
+        assert_eq!(
+            daemon.request().line,
+            "POST /v1.44/containers/prune HTTP/1.1"
+        );
+        assert_eq!(pruned.containers_deleted, None);
+        assert_eq!(pruned.space_reclaimed, Some(0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn report_state_changes() -> Result<()> {
+        let daemon = MockDaemon::start([
+            "HTTP/1.1 204 No Content\r\n\r\n",
+            "HTTP/1.1 304 Not Modified\r\n\r\n",
+            "HTTP/1.1 304 Not Modified\r\n\r\n",
+        ]);
+        let docker = daemon.docker();
+        let container = docker.containers().get("web");
+
+        assert_eq!(container.start(None)?, StateChange::Changed);
+        // The daemon answers `304 Not Modified` when the container is already in the state.
+        assert_eq!(container.start(None)?, StateChange::AlreadyInState);
+        assert_eq!(container.stop(None)?, StateChange::AlreadyInState);
+        assert!(!StateChange::AlreadyInState.is_changed());
+
+        assert_eq!(
+            daemon.request().line,
+            "POST /v1.44/containers/web/start HTTP/1.1"
+        );
+        assert_eq!(
+            daemon.request().line,
+            "POST /v1.44/containers/web/start HTTP/1.1"
+        );
+        assert_eq!(
+            daemon.request().line,
+            "POST /v1.44/containers/web/stop HTTP/1.1"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn encode_container_names() -> Result<()> {
+        let daemon = MockDaemon::start(["HTTP/1.1 204 No Content\r\n\r\n"]);
+        let docker = daemon.docker();
+
+        docker.containers().get("web?signal=KILL#x").kill(None)?;
+        assert_eq!(
+            daemon.request().line,
+            "POST /v1.44/containers/web%3Fsignal=KILL%23x/kill HTTP/1.1"
+        );
+
+        let err = docker.containers().get("..").remove(None).unwrap_err();
+        assert!(err.to_string().contains("not a valid path segment"));
+
+        Ok(())
+    }
+}
@@ -1,75 +1,1180 @@
+use std::collections::hash_map::RandomState;
 use std::env;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, Read};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use serde::Serialize;
+use shiprs_http::uri::Endpoint;
+pub use shiprs_http::{uri::PathTemplate, Limits, Method, Request, Response};
+use shiprs_http::{Error as HttpError, HeaderMap, HttpParsingKind, RequestBuilder};
+use shiprs_models::models::{BuildPruneResponse, ErrorResponse};
 
-use crate::error::Result;
-use crate::transport::Transport;
+use crate::build::BuildPruneOption;
+use crate::container::StateChange;
+use crate::error::{DockerApiResponse, Error, Result};
+use crate::interceptor::{Exchange, Interceptor};
+use crate::stream::BodyStream;
+use crate::transport::{self, Transport};
 
 pub struct Docker {
-    transport: Transport,
+    transport: Arc<Transport>,
+    timeouts: Timeouts,
+    retry: Option<RetryPolicy>,
+    interceptors: Arc<[Arc<dyn Interceptor>]>,
+    defaults: Arc<Defaults>,
+}
+
+/// The settings applied to every request sent by a client, see [`DockerBuilder`].
+#[derive(Debug)]
+struct Defaults {
+    host: Option<String>,
+    headers: HeaderMap,
+    path_prefix: Option<String>,
 }
 
 impl Docker {
+    /// Connect to the docker daemon given by the `DOCKER_HOST` environment variable,
+    /// or to `/var/run/docker.sock` by default.
     pub fn new() -> Result<Self> {
-        match env::var("DOCKER_HOST").ok() {
-            Some(host) => {
-                #[cfg(feature = "unix-socket")]
-                if let Some(socket) = host.strip_prefix("unix://") {
-                    return Docker::unix(socket);
-                }
-                panic!("Only unix sockets are supported yet");
-            }
-            #[cfg(feature = "unix-socket")]
-            None => Docker::unix("/var/run/docker.sock"),
-            #[cfg(not(feature = "unix-socket"))]
-            None => {
-                panic!("Only unix sockets are supported yet");
-            }
+        Docker::builder().build()
+    }
+
+    /// Configure the client before connecting to the daemon.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use std::time::Duration;
+    /// use shiprs::docker::Timeouts;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::builder()
+    ///     .unix("/run/user/1000/docker.sock")
+    ///     .timeouts(Timeouts {
+    ///         connect: Some(Duration::from_secs(1)),
+    ///         read: Some(Duration::from_secs(30)),
+    ///         ..Default::default()
+    ///     })
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn builder() -> DockerBuilder {
+        DockerBuilder::default()
+    }
+
+    /// A client sharing the connection of this one, whose requests use `timeouts` instead.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use std::time::Duration;
+    /// use shiprs::docker::Timeouts;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    ///
+    /// // Give up on the inspection if the daemon does not answer within a second.
+    /// let timeouts = Timeouts {
+    ///     read: Some(Duration::from_secs(1)),
+    ///     ..Default::default()
+    /// };
+    /// let container = docker
+    ///     .with_timeouts(timeouts)
+    ///     .containers()
+    ///     .get("insert container id here")
+    ///     .inspect(None)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_timeouts(&self, timeouts: Timeouts) -> Docker {
+        Docker {
+            transport: self.transport.clone(),
+            timeouts,
+            retry: self.retry.clone(),
+            interceptors: self.interceptors.clone(),
+            defaults: self.defaults.clone(),
         }
     }
 
-    #[cfg(feature = "unix-socket")]
-    pub(crate) fn unix<S: Into<String>>(socket: S) -> Result<Self> {
-        Ok(Docker {
-            transport: Transport::unix(socket)?,
-        })
+    /// Send a request to any endpoint of the API, including the ones not covered by this crate yet.
+    ///
+    /// The `query` is URL-encoded after the `path` and the `body` is sent serialized as JSON.
+    /// Identifiers interpolated in the `path` should be encoded with a [`PathTemplate`].
+    /// As for every other request, `3xx`, `4xx` and `5xx` responses are returned as
+    /// [`ErrorKind::DockerApiResponse`](crate::error::ErrorKind::DockerApiResponse) errors.
+    ///
+    /// Fails without sending anything if the `query` cannot be URL-encoded, e.g. because it holds
+    /// nested structs, or if the `body` cannot be serialized as JSON.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::docker::{Method, PathTemplate};
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    ///
+    /// let path = PathTemplate::new("/containers/{}/top")
+    ///     .segment("insert container id here")
+    ///     .build()?;
+    /// let res = docker.raw(Method::Get, &path, Some([("ps_args", "aux")]), None::<()>)?;
+    ///
+    /// let processes: serde_json::Value = serde_json::from_slice(res.body())?;
+    /// println!("{}", processes);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn raw<'p, P, O, B>(
+        &self,
+        method: Method,
+        path: P,
+        query: Option<O>,
+        body: Option<B>,
+    ) -> Result<Response>
+    where
+        P: Into<Endpoint<'p>>,
+        O: Serialize,
+        B: Serialize,
+    {
+        self.request(raw_request(method, path, query, body)?)
     }
 
-    pub(crate) fn request<S>(&self, req: shiprs_http::Request<S>) -> Result<shiprs_http::Response>
+    /// Send a request to any endpoint of the API, like [`Docker::raw`], streaming the body of the
+    /// response instead of reading it into memory.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use std::io::{BufRead, BufReader};
+    /// use shiprs::docker::Method;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    ///
+    /// let events = docker.raw_stream(
+    ///     Method::Get,
+    ///     "/events",
+    ///     Some([("since", "1h")]),
+    ///     None::<()>,
+    /// )?;
+    /// for event in BufReader::new(events).lines() {
+    ///     println!("{}", event?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn raw_stream<'p, P, O, B>(
+        &self,
+        method: Method,
+        path: P,
+        query: Option<O>,
+        body: Option<B>,
+    ) -> Result<BodyStream>
     where
-        S: Serialize,
+        P: Into<Endpoint<'p>>,
+        O: Serialize,
+        B: Serialize,
     {
-        let res = self.transport.request(req)?;
+        self.process_into_stream(raw_request(method, path, query, body)?)
+    }
 
-        match res.status() {
-            200..=399 => Ok(res),
-            400..=599 => {
-                let status = res.status();
-                let err = serde_json::from_slice(res.body())?;
-                Err(crate::error::Error::docker_api_response(status, err))
+    /// The limits applied when parsing the responses of the daemon.
+    pub(crate) fn limits(&self) -> &Limits {
+        self.transport.limits()
+    }
+
+    /// Send `req` with `send`, sending it again according to the [`RetryPolicy`] if it is
+    /// idempotent and fails.
+    fn send<T>(
+        &self,
+        mut req: shiprs_http::Request,
+        send: impl Fn(shiprs_http::Request) -> Result<T>,
+        response: impl Fn(&T) -> &shiprs_http::Response,
+    ) -> Result<T> {
+        self.apply_defaults(&mut req);
+
+        let Some(policy) = self.retry.as_ref().filter(|_| req.is_idempotent()) else {
+            return self.intercept(req, send, response);
+        };
+
+        let start = Instant::now();
+        let mut retries = 0;
+        loop {
+            let next = match retries < policy.max_retries {
+                true => req.try_clone(),
+                false => None,
+            };
+            let result = self.intercept(req, &send, &response);
+            let Some(next) = next else {
+                return result;
+            };
+
+            let retry = match &result {
+                Ok(res) => policy.statuses.contains(&response(res).status()),
+                Err(err) => err.is_connection_error(),
+            };
+            let backoff = policy.backoff(retries);
+            let expired = policy
+                .max_elapsed
+                .is_some_and(|max| start.elapsed() + backoff > max);
+            if !retry || expired {
+                return result;
             }
-            _ => unreachable!("unexpected status code: {}", res.status()),
+
+            // Release the connection of a streamed response before waiting.
+            drop(result);
+            thread::sleep(backoff);
+            retries += 1;
+            req = next;
         }
     }
 
-    pub(crate) fn process_into_value<S, T>(&self, req: shiprs_http::Request<S>) -> Result<T>
+    /// Add the default headers to `req`, unless it already has them, and pin the API version.
+    fn apply_defaults(&self, req: &mut shiprs_http::Request) {
+        if let Some(host) = &self.defaults.host {
+            req.headers_mut().insert("Host", host.as_str());
+        }
+        for (name, value) in self.defaults.headers.iter() {
+            if !req.headers().contains(name) {
+                req.headers_mut().append(name, value);
+            }
+        }
+        if let Some(prefix) = &self.defaults.path_prefix {
+            req.prefix_path(prefix);
+        }
+    }
+
+    /// Send `req` through the interceptors and the transport, with `send`.
+    fn intercept<T>(
+        &self,
+        mut req: shiprs_http::Request,
+        send: impl FnOnce(shiprs_http::Request) -> Result<T>,
+        response: impl Fn(&T) -> &shiprs_http::Response,
+    ) -> Result<T> {
+        if self.interceptors.is_empty() {
+            return send(req);
+        }
+
+        for interceptor in self.interceptors.iter() {
+            interceptor.before_request(&mut req);
+        }
+        let method = req.method();
+        let endpoint = req.endpoint().to_string();
+        let uri = req.uri().to_string();
+
+        let start = Instant::now();
+        let result = send(req);
+        let exchange = Exchange {
+            method,
+            endpoint: &endpoint,
+            uri: &uri,
+            elapsed: start.elapsed(),
+            result: result.as_ref().map(&response),
+        };
+        for interceptor in self.interceptors.iter() {
+            interceptor.after_response(&exchange);
+        }
+
+        result
+    }
+
+    pub(crate) fn request(&self, req: shiprs_http::Request) -> Result<shiprs_http::Response> {
+        let res = self.send(
+            req,
+            |req| self.transport.request(req, &self.timeouts),
+            |res| res,
+        )?;
+
+        // Any other status, including an unexpected one such as a 1xx, is reported as an error.
+        match res.status() {
+            200..=299 => Ok(res),
+            _ => Err(api_error(&res, res.body())),
+        }
+    }
+
+    pub(crate) fn process_into_value<T>(&self, req: shiprs_http::Request) -> Result<T>
     where
-        S: Serialize,
         T: for<'de> serde::Deserialize<'de>,
     {
         let res = self.request(req)?;
         serde_json::from_slice(res.body()).map_err(Into::into)
     }
 
-    pub(crate) fn process_into_unit<S>(&self, req: shiprs_http::Request<S>) -> Result<()>
-    where
-        S: Serialize,
-    {
+    pub(crate) fn process_into_unit(&self, req: shiprs_http::Request) -> Result<()> {
         let _ = self.request(req)?;
         Ok(())
     }
 
-    pub fn containers(&self) -> crate::container::Containers {
+    /// Send a request changing the state of an object, which the daemon answers with
+    /// `304 Not Modified` when the object is already in the requested state.
+    pub(crate) fn process_into_state_change(
+        &self,
+        req: shiprs_http::Request,
+    ) -> Result<StateChange> {
+        match self.request(req) {
+            Ok(_) => Ok(StateChange::Changed),
+            Err(err) if err.is_not_modified() => Ok(StateChange::AlreadyInState),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Send a request whose response body is streamed instead of being read into memory.
+    pub(crate) fn process_into_stream(&self, req: shiprs_http::Request) -> Result<BodyStream> {
+        let (res, mut body) = self.send(
+            req,
+            |req| self.transport.stream(req, &self.timeouts),
+            |(res, _): &(_, transport::Body)| res,
+        )?;
+
+        match res.status() {
+            200..=299 => Ok(BodyStream::new(body, res.headers().clone())),
+            _ => {
+                // The error is read into memory, so it is bounded like any other buffered body.
+                let max = self.limits().max_body_size;
+                let mut buf = Vec::new();
+                (&mut body).take(max as u64 + 1).read_to_end(&mut buf)?;
+                if buf.len() > max {
+                    return Err(HttpError::from(HttpParsingKind::BodyTooLarge).into());
+                }
+                Err(api_error(&res, &buf))
+            }
+        }
+    }
+
+    pub fn configs(&self) -> crate::config::Configs<'_> {
+        crate::config::Configs::new(self)
+    }
+
+    pub fn containers(&self) -> crate::container::Containers<'_> {
         crate::container::Containers::new(self)
     }
+
+    pub fn images(&self) -> crate::image::Images<'_> {
+        crate::image::Images::new(self)
+    }
+
+    pub fn nodes(&self) -> crate::node::Nodes<'_> {
+        crate::node::Nodes::new(self)
+    }
+
+    pub fn plugins(&self) -> crate::plugin::Plugins<'_> {
+        crate::plugin::Plugins::new(self)
+    }
+
+    pub fn secrets(&self) -> crate::secret::Secrets<'_> {
+        crate::secret::Secrets::new(self)
+    }
+
+    pub fn services(&self) -> crate::service::Services<'_> {
+        crate::service::Services::new(self)
+    }
+
+    pub fn swarm(&self) -> crate::swarm::Swarm<'_> {
+        crate::swarm::Swarm::new(self)
+    }
+
+    pub fn tasks(&self) -> crate::task::Tasks<'_> {
+        crate::task::Tasks::new(self)
+    }
+
+    /// Delete builder cache.
+    /// This corresponds to the `POST /build/prune` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Image/operation/BuildPrune) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::build::{BuildPruneFilter, BuildPruneOption};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let options = BuildPruneOption {
+    ///     all: true,
+    ///     filters: vec![BuildPruneFilter::Until("24h")],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let pruned = docker.prune_build_cache(Some(options))?;
+    /// println!("Reclaimed {:?} bytes", pruned.space_reclaimed);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn prune_build_cache<T>(
+        &self,
+        options: Option<BuildPruneOption<T>>,
+    ) -> Result<BuildPruneResponse>
+    where
+        T: AsRef<str> + Serialize,
+    {
+        let url = "/build/prune";
+        let request = RequestBuilder::<BuildPruneOption<T>, ()>::post(url)
+            .query(options)
+            .build()?;
+
+        self.process_into_value(request)
+    }
+}
+
+/// Builder of a [`Docker`] client, created with [`Docker::builder`].
+#[derive(Clone, Default)]
+pub struct DockerBuilder {
+    socket: Option<String>,
+    timeouts: Timeouts,
+    limits: Limits,
+    retry: Option<RetryPolicy>,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    user_agent: Option<String>,
+    host: Option<String>,
+    headers: HeaderMap,
+    api_version: Option<String>,
+    unversioned: bool,
+}
+
+impl DockerBuilder {
+    /// Connect to the daemon listening on the unix socket at `socket`,
+    /// instead of the one given by the `DOCKER_HOST` environment variable.
+    #[cfg(feature = "unix-socket")]
+    pub fn unix<S: Into<String>>(mut self, socket: S) -> Self {
+        self.socket = Some(socket.into());
+        self
+    }
+
+    /// The default timeouts of the requests sent by the client.
+    pub fn timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// The maximum sizes of the responses accepted from the daemon.
+    ///
+    /// The defaults are generous, they should only need to be raised for unusually large
+    /// responses, e.g. when listing tens of thousands of objects.
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Send idempotent requests again when they fail on a connection error or a transient
+    /// server error. Requests are not retried by default.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use std::time::Duration;
+    /// use shiprs::docker::RetryPolicy;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// // Wait for the daemon to come back for up to a minute.
+    /// let docker = Docker::builder()
+    ///     .retry(RetryPolicy {
+    ///         max_retries: 10,
+    ///         max_elapsed: Some(Duration::from_secs(60)),
+    ///         ..Default::default()
+    ///     })
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Call `interceptor` around every request sent by the client, after the interceptors
+    /// registered before it. See [`Interceptor`].
+    pub fn interceptor(mut self, interceptor: Arc<dyn Interceptor>) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// The `User-Agent` of the requests, `shiprs/<version>` by default.
+    pub fn user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// The `Host` of the requests, `localhost` by default. Setting the `Host` with
+    /// [`header`](DockerBuilder::header) is the same as calling this method.
+    ///
+    /// The daemon ignores it, but a proxy in front of the socket may route requests with it.
+    pub fn host<S: Into<String>>(mut self, host: S) -> Self {
+        self.host = Some(host.into());
+        self
+    }
+
+    /// Add a header to every request, unless the request sets it itself.
+    ///
+    /// The `Host` header is set on every request, so it is given to
+    /// [`host`](DockerBuilder::host) instead.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::builder()
+    ///     .unix("/run/docker-ci.sock")
+    ///     .user_agent("deploy-bot/2.1")
+    ///     .header("X-Request-Origin", "deploy-bot")
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn header<K, V>(mut self, name: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let name = name.into();
+        match name.eq_ignore_ascii_case("Host") {
+            true => self.host = Some(value.into()),
+            false => self.headers.append(name, value),
+        }
+        self
+    }
+
+    /// The version of the API the requests are sent to, e.g. `1.43`.
+    ///
+    /// By default, requests are sent to the version the [models](shiprs_models) are generated
+    /// from, `1.44`. The daemon then answers as it would for that version, and refuses the
+    /// requests if it does not support it.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// // Requests are sent to `/v1.43/containers/json`, etc.
+    /// let docker = Docker::builder().api_version("1.43").build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn api_version<S: Into<String>>(mut self, version: S) -> Self {
+        self.api_version = Some(version.into());
+        self.unversioned = false;
+        self
+    }
+
+    /// Send the requests to unversioned paths, e.g. `/containers/json`, so that the daemon
+    /// answers with the latest version of the API it supports.
+    ///
+    /// The responses may then hold fields the models don't know about, which are ignored, or
+    /// miss fields they expect.
+    pub fn unversioned(mut self) -> Self {
+        self.api_version = None;
+        self.unversioned = true;
+        self
+    }
+
+    /// Connect to the daemon.
+    ///
+    /// Fails if no socket is given and the `DOCKER_HOST` environment variable is not a unix
+    /// socket, e.g. `tcp://localhost:2375`, or if a header is not valid, e.g. because its value
+    /// holds a line break.
+    pub fn build(self) -> Result<Docker> {
+        let socket = match self.socket {
+            Some(socket) => socket,
+            None => default_socket()?,
+        };
+
+        let mut headers = self.headers;
+        match self.user_agent {
+            Some(user_agent) => headers.insert("User-Agent", user_agent),
+            None if !headers.contains("User-Agent") => {
+                headers.insert("User-Agent", concat!("shiprs/", env!("CARGO_PKG_VERSION")))
+            }
+            None => {}
+        }
+        headers.validate()?;
+        if let Some(host) = &self.host {
+            HeaderMap::from_iter([("Host", host.as_str())]).validate()?;
+        }
+        let defaults = Defaults {
+            host: self.host,
+            headers,
+            path_prefix: match (self.api_version, self.unversioned) {
+                (_, true) => None,
+                (Some(version), false) => Some(format!("/v{}", version.trim_start_matches('v'))),
+                (None, false) => Some(shiprs_models::BASE_PATH.to_string()),
+            },
+        };
+
+        Ok(Docker {
+            transport: Arc::new(Transport::unix(socket, &self.timeouts, self.limits)?),
+            timeouts: self.timeouts,
+            retry: self.retry,
+            interceptors: self.interceptors.into(),
+            defaults: Arc::new(defaults),
+        })
+    }
+}
+
+impl std::fmt::Debug for DockerBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DockerBuilder")
+            .field("socket", &self.socket)
+            .field("timeouts", &self.timeouts)
+            .field("limits", &self.limits)
+            .field("retry", &self.retry)
+            .field("interceptors", &self.interceptors.len())
+            .field("user_agent", &self.user_agent)
+            .field("host", &self.host)
+            .field("headers", &self.headers)
+            .field("api_version", &self.api_version)
+            .field("unversioned", &self.unversioned)
+            .finish()
+    }
+}
+
+/// The socket given by the `DOCKER_HOST` environment variable, or the default one.
+fn default_socket() -> Result<String> {
+    match env::var("DOCKER_HOST").ok() {
+        #[cfg(feature = "unix-socket")]
+        Some(host) if host.starts_with("unix://") => Ok(host["unix://".len()..].to_string()),
+        Some(host) => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "unsupported DOCKER_HOST {:?}, only unix sockets are supported yet",
+                host
+            ),
+        )
+        .into()),
+        #[cfg(feature = "unix-socket")]
+        None => Ok("/var/run/docker.sock".to_string()),
+        #[cfg(not(feature = "unix-socket"))]
+        None => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "only unix sockets are supported yet",
+        )
+        .into()),
+    }
+}
+
+/// Timeouts of the requests sent to the daemon. No timeout is set by default.
+///
+/// A request failing on a timeout returns an error of kind [`ErrorKind::Timeout`](crate::error::ErrorKind::Timeout).
+/// The durations must not be zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timeouts {
+    /// Maximum duration to connect to the daemon.
+    pub connect: Option<Duration>,
+
+    /// Maximum duration to wait for data from the daemon while reading a response.
+    pub read: Option<Duration>,
+
+    /// Maximum duration to wait for the daemon to accept data while sending a request.
+    pub write: Option<Duration>,
+
+    /// Maximum duration to wait for data while reading a streamed body, such as logs or an
+    /// exported image. The `read` timeout only applies to the headers of streamed responses,
+    /// since the daemon may rightfully keep them open and silent for a long time.
+    pub stream_idle: Option<Duration>,
+}
+
+fn raw_request<'a, P, O, B>(
+    method: Method,
+    path: P,
+    query: Option<O>,
+    body: Option<B>,
+) -> Result<Request<'a>>
+where
+    P: Into<Endpoint<'a>>,
+    O: Serialize,
+    B: Serialize,
+{
+    let mut request = RequestBuilder::<O, B>::new(method, path).query(query);
+    if let Some(body) = body {
+        request = request.body(body);
+    }
+
+    Ok(request.build()?)
+}
+
+/// When and how often a request is sent again after a failure, see [`DockerBuilder::retry`].
+///
+/// Only idempotent requests are retried: `GET` and `HEAD` requests, as well as the few other
+/// requests which can safely be sent several times, such as starting or stopping a container.
+/// Requests uploading a stream, e.g. an image to import, are never retried.
+///
+/// A request is only sent again after a connection error if the connection failed before any of
+/// the response was received. The daemon may still have processed the first attempt: a container
+/// started by it is then reported as
+/// [`StateChange::AlreadyInState`](crate::container::StateChange::AlreadyInState) by the retry.
+///
+/// The delay before the `n`-th retry is picked at random between half and all of
+/// `initial_backoff * 2^n`, capped at `max_backoff`, so that clients don't retry in lockstep.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of times a request is sent again.
+    pub max_retries: u32,
+
+    /// Delay before the first retry.
+    pub initial_backoff: Duration,
+
+    /// Maximum delay between two retries.
+    pub max_backoff: Duration,
+
+    /// Time after which a request is not retried anymore, counting from when it was first sent.
+    pub max_elapsed: Option<Duration>,
+
+    /// Response statuses after which a request is retried, e.g. `503 Service Unavailable` while
+    /// the daemon starts. Requests are always retried when the connection fails before any of
+    /// the response is received, e.g. when the daemon restarted.
+    pub statuses: Vec<u16>,
+}
+
+impl RetryPolicy {
+    /// The delay before the retry following `retries` previous ones.
+    fn backoff(&self, retries: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(retries))
+            .min(self.max_backoff);
+        let half = backoff / 2;
+
+        half + jitter(backoff - half)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+            max_elapsed: Some(Duration::from_secs(10)),
+            statuses: vec![500, 502, 503, 504],
+        }
+    }
+}
+
+/// A random duration up to `max`.
+fn jitter(max: Duration) -> Duration {
+    // Each `RandomState` is seeded differently, which is random enough for a backoff.
+    let random = RandomState::new().build_hasher().finish();
+    let nanos = max.as_nanos() as u64;
+
+    Duration::from_nanos(random % nanos.saturating_add(1))
+}
+
+/// Build the error returned for a `3xx`, `4xx` or `5xx` response.
+///
+/// The daemon describes errors with an `ErrorResponse` JSON body. Any other body, e.g. a plain-text
+/// message from a proxy or no body at all, is used as is for the message, falling back to the reason phrase.
+fn api_error(res: &shiprs_http::Response, body: &[u8]) -> Error {
+    let inner = serde_json::from_slice(body).unwrap_or_else(|_| {
+        let text = String::from_utf8_lossy(body);
+        let message = match text.trim() {
+            "" => res.reason().to_string(),
+            text => text.to_string(),
+        };
+        ErrorResponse { message }
+    });
+
+    Error::docker_api_response(DockerApiResponse {
+        inner,
+        status: res.status(),
+        headers: res.headers().clone(),
+        body: body.to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::Read;
+
+    use super::*;
+    use crate::mock::{json_response, MockDaemon};
+
+    #[test]
+    fn send_raw_request() -> Result<()> {
+        let daemon = MockDaemon::start([
+            json_response(201, r#"{"Id":"4fa6e0f0c678"}"#),
+            json_response(404, r#"{"message":"page not found"}"#),
+        ]);
+        let docker = daemon.docker();
+
+        let body = serde_json::json!({ "Image": "alpine", "Cmd": ["true"] });
+        let res = docker.raw(
+            Method::Post,
+            "/containers/create",
+            Some([("name", "web")]),
+            Some(&body),
+        )?;
+
+        let request = daemon.request();
+        assert_eq!(
+            request.line,
+            "POST /v1.44/containers/create?name=web HTTP/1.1"
+        );
+        assert_eq!(request.header("Content-Type"), Some("application/json"));
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&request.body)?,
+            body
+        );
+        assert_eq!(res.status(), 201);
+        assert_eq!(res.body(), br#"{"Id":"4fa6e0f0c678"}"#);
+
+        let err = docker
+            .raw(Method::Get, "/experimental", None::<()>, None::<()>)
+            .unwrap_err();
+        assert_eq!(daemon.request().line, "GET /v1.44/experimental HTTP/1.1");
+        assert!(err.is_not_found());
+        assert_eq!(err.to_string(), "page not found");
+
+        Ok(())
+    }
+
+    #[derive(Serialize)]
+    struct Filters {
+        label: Vec<&'static str>,
+    }
+
+    #[test]
+    fn refuse_unserializable_raw_request() -> Result<()> {
+        let daemon = MockDaemon::start([json_response(200, "[]")]);
+        let docker = daemon.docker();
+
+        let query = [(
+            "filters",
+            Filters {
+                label: vec!["app=web"],
+            },
+        )];
+        let err = docker
+            .raw(Method::Get, "/containers/json", Some(query), None::<()>)
+            .unwrap_err();
+        assert!(matches!(err.kind(), crate::error::ErrorKind::ShiprsHttp));
+
+        let body = HashMap::from([(vec!["app"], "web")]);
+        let err = docker
+            .raw_stream(Method::Post, "/containers/create", None::<()>, Some(body))
+            .unwrap_err();
+        assert!(matches!(err.kind(), crate::error::ErrorKind::ShiprsHttp));
+
+        docker.raw(Method::Get, "/containers/json", None::<()>, None::<()>)?;
+        assert_eq!(daemon.request().line, "GET /v1.44/containers/json HTTP/1.1");
+
+        Ok(())
+    }
+
+    #[test]
+    fn stream_raw_request() -> Result<()> {
+        let daemon = MockDaemon::start([
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n6\r\n{}\n{}\n\r\n0\r\n\r\n",
+        ]);
+
+        let mut events = daemon.docker().raw_stream(
+            Method::Get,
+            "/events",
+            Some([("since", "1h")]),
+            None::<()>,
+        )?;
+        let mut buf = String::new();
+        events.read_to_string(&mut buf)?;
+
+        assert_eq!(daemon.request().line, "GET /v1.44/events?since=1h HTTP/1.1");
+        assert_eq!(buf, "{}\n{}\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn refuse_large_streamed_errors() -> Result<()> {
+        let daemon = MockDaemon::start([
+            json_response(500, r#"{"message":"something went wrong"}"#),
+            json_response(500, r#"{"message":"error"}"#),
+        ]);
+        let docker = daemon
+            .builder()
+            .limits(Limits {
+                max_body_size: 20,
+                ..Default::default()
+            })
+            .build()?;
+
+        let err = docker
+            .raw_stream(Method::Get, "/events", None::<()>, None::<()>)
+            .unwrap_err();
+        let source = std::error::Error::source(&err)
+            .and_then(|source| source.downcast_ref::<HttpError>())
+            .expect("not an HTTP error");
+        assert!(matches!(
+            source.kind(),
+            shiprs_http::ErrorKind::HttpParsing(HttpParsingKind::BodyTooLarge)
+        ));
+
+        let err = docker
+            .raw_stream(Method::Get, "/events", None::<()>, None::<()>)
+            .unwrap_err();
+        assert_eq!(err.status(), Some(500));
+        assert_eq!(err.to_string(), "error");
+
+        Ok(())
+    }
+
+    #[test]
+    fn report_unexpected_statuses() -> Result<()> {
+        let daemon = MockDaemon::start([
+            "HTTP/1.1 199 Odd\r\nContent-Length: 0\r\n\r\n".to_string(),
+            json_response(600, r#"{"message":"odd"}"#),
+            "HTTP/1.1 199 Odd\r\nContent-Length: 0\r\n\r\n".to_string(),
+        ]);
+        let docker = daemon.docker();
+
+        let err = docker
+            .raw(Method::Get, "/_ping", None::<()>, None::<()>)
+            .unwrap_err();
+        assert_eq!(err.status(), Some(199));
+        assert_eq!(err.to_string(), "Odd");
+
+        let err = docker
+            .raw(Method::Get, "/_ping", None::<()>, None::<()>)
+            .unwrap_err();
+        assert_eq!(err.status(), Some(600));
+        assert_eq!(err.to_string(), "odd");
+
+        let err = docker
+            .raw_stream(Method::Get, "/events", None::<()>, None::<()>)
+            .unwrap_err();
+        assert_eq!(err.status(), Some(199));
+
+        Ok(())
+    }
+
+    #[test]
+    fn refuse_unsupported_docker_host() {
+        let previous = env::var_os("DOCKER_HOST");
+        env::set_var("DOCKER_HOST", "tcp://localhost:2375");
+        let result = Docker::builder().build();
+        match previous {
+            Some(host) => env::set_var("DOCKER_HOST", host),
+            None => env::remove_var("DOCKER_HOST"),
+        }
+
+        let Err(err) = result else {
+            panic!("connected to an unsupported DOCKER_HOST");
+        };
+
+        assert!(matches!(err.kind(), crate::error::ErrorKind::Io));
+        assert!(err.to_string().contains("tcp://localhost:2375"), "{}", err);
+    }
+
+    #[test]
+    fn refuse_invalid_headers() -> Result<()> {
+        let daemon = MockDaemon::start([json_response(200, r#"{"Id":"4fa6e0f0c678"}"#)]);
+
+        for builder in [
+            daemon
+                .builder()
+                .header("X-Origin", "deploy-bot\r\nX-Injected: 1"),
+            daemon.builder().header("X Origin", "deploy-bot"),
+            daemon.builder().user_agent("deploy-bot\n"),
+            daemon
+                .builder()
+                .host("docker-ci\r\n\r\nGET /secrets HTTP/1.1"),
+        ] {
+            let Err(err) = builder.build() else {
+                panic!("built a client with an invalid header");
+            };
+            assert!(matches!(err.kind(), crate::error::ErrorKind::ShiprsHttp));
+        }
+
+        // Headers set by interceptors are checked before the request is sent.
+        struct Inject;
+
+        impl Interceptor for Inject {
+            fn before_request(&self, request: &mut Request<'_>) {
+                request
+                    .headers_mut()
+                    .insert("X-Origin", "tests\r\nX-Injected: 1");
+            }
+        }
+
+        let docker = daemon.builder().interceptor(Arc::new(Inject)).build()?;
+        let err = docker.containers().get("web").inspect(None).unwrap_err();
+        assert!(matches!(err.kind(), crate::error::ErrorKind::ShiprsHttp));
+
+        daemon.docker().containers().get("web").inspect(None)?;
+        let request = daemon.request();
+        assert_eq!(request.line, "GET /v1.44/containers/web/json HTTP/1.1");
+        assert_eq!(request.header("X-Injected"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn default_request_settings() -> Result<()> {
+        let daemon = MockDaemon::start([
+            json_response(200, r#"{"Id":"4fa6e0f0c678"}"#),
+            json_response(200, r#"{"Id":"4fa6e0f0c678"}"#),
+            json_response(200, r#"{"Id":"4fa6e0f0c678"}"#),
+        ]);
+
+        daemon.docker().containers().get("web").inspect(None)?;
+        let request = daemon.request();
+        assert_eq!(request.line, "GET /v1.44/containers/web/json HTTP/1.1");
+        assert_eq!(request.header("Host"), Some("localhost"));
+        assert_eq!(
+            request.header("User-Agent"),
+            Some(concat!("shiprs/", env!("CARGO_PKG_VERSION")))
+        );
+
+        let docker = daemon
+            .builder()
+            .user_agent("deploy-bot/2.1")
+            .host("docker-ci")
+            .header("X-Request-Origin", "deploy-bot")
+            .header("Content-Type", "text/plain")
+            .api_version("v1.43")
+            .build()?;
+        docker.containers().get("web").inspect(None)?;
+
+        let request = daemon.request();
+        assert_eq!(request.line, "GET /v1.43/containers/web/json HTTP/1.1");
+        assert_eq!(request.header("Host"), Some("docker-ci"));
+        assert_eq!(request.header("User-Agent"), Some("deploy-bot/2.1"));
+        assert_eq!(request.header("X-Request-Origin"), Some("deploy-bot"));
+        // The headers set by the request take precedence.
+        assert_eq!(request.header("Content-Type"), Some("application/json"));
+
+        let docker = daemon
+            .builder()
+            .header("host", "docker-ci")
+            .unversioned()
+            .build()?;
+        docker.containers().get("web").inspect(None)?;
+
+        let request = daemon.request();
+        assert_eq!(request.line, "GET /containers/web/json HTTP/1.1");
+        assert_eq!(request.header("Host"), Some("docker-ci"));
+
+        Ok(())
+    }
+
+    fn retry_policy(max_retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn retry_idempotent_requests() -> Result<()> {
+        let daemon = MockDaemon::start([
+            String::new(),
+            json_response(503, r#"{"message":"daemon is starting"}"#),
+            json_response(200, r#"{"Id":"4fa6e0f0c678"}"#),
+        ]);
+        let docker = daemon.builder().retry(retry_policy(3)).build()?;
+
+        let container = docker.containers().get("web").inspect(None)?;
+        assert_eq!(container.id.as_deref(), Some("4fa6e0f0c678"));
+        for _ in 0..3 {
+            assert_eq!(
+                daemon.request().line,
+                "GET /v1.44/containers/web/json HTTP/1.1"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn retry_streamed_requests() -> Result<()> {
+        let daemon = MockDaemon::start([
+            String::new(),
+            "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello".to_string(),
+        ]);
+        let docker = daemon.builder().retry(retry_policy(1)).build()?;
+
+        let mut body = String::new();
+        docker
+            .raw_stream(Method::Get, "/events", None::<()>, None::<()>)?
+            .read_to_string(&mut body)?;
+        assert_eq!(body, "hello");
+
+        Ok(())
+    }
+
+    #[test]
+    fn do_not_retry_answered_requests() -> Result<()> {
+        let daemon = MockDaemon::start([
+            "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 30\r\n\r\n{\"Id\"".to_string(),
+            json_response(200, r#"{"Id":"4fa6e0f0c678"}"#),
+        ]);
+        let docker = daemon.builder().retry(retry_policy(3)).build()?;
+
+        // The daemon answered before closing the connection, so the request is not sent again.
+        let err = docker.containers().get("web").inspect(None).unwrap_err();
+        assert!(err.api_response().is_none());
+        daemon.request();
+
+        let container = docker.containers().get("web").inspect(None)?;
+        assert_eq!(container.id.as_deref(), Some("4fa6e0f0c678"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn do_not_retry_other_requests() -> Result<()> {
+        let daemon = MockDaemon::start([
+            String::new(),
+            "HTTP/1.1 204 No Content\r\n\r\n".to_string(),
+            json_response(503, r#"{"message":"daemon is starting"}"#),
+            json_response(503, r#"{"message":"daemon is starting"}"#),
+            json_response(503, r#"{"message":"daemon is starting"}"#),
+        ]);
+        let docker = daemon.builder().retry(retry_policy(1)).build()?;
+        let container = docker.containers().get("web");
+
+        // Restarting twice is not the same as restarting once.
+        assert!(container.restart(None).is_err());
+        container.restart(None)?;
+
+        // Give up after the last retry.
+        let err = container.inspect(None).unwrap_err();
+        assert_eq!(err.status(), Some(503));
+
+        // Requests are not retried without a policy.
+        let err = daemon.docker().containers().get("web").inspect(None);
+        assert_eq!(err.unwrap_err().status(), Some(503));
+
+        Ok(())
+    }
+
+    #[test]
+    fn jittered_backoff() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+            ..Default::default()
+        };
+
+        for _ in 0..100 {
+            let backoff = policy.backoff(0);
+            assert!(backoff >= Duration::from_millis(50) && backoff <= Duration::from_millis(100));
+
+            let backoff = policy.backoff(2);
+            assert!(backoff >= Duration::from_millis(200) && backoff <= Duration::from_millis(400));
+
+            let backoff = policy.backoff(40);
+            assert!(backoff >= Duration::from_secs(1) && backoff <= Duration::from_secs(2));
+        }
+    }
 }
@@ -14,6 +14,8 @@ pub struct Error {
 struct ErrorImpl {
     kind: ErrorKind,
     error: Box<dyn StdError + Send + Sync>,
+    /// Whether the request failed before the daemon answered, see [`Error::unanswered`].
+    unanswered: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -23,6 +25,9 @@ pub enum ErrorKind {
     SerdeUrlEncoded,
     ShiprsHttp,
     DockerApiResponse,
+    DockerStream,
+    /// A connection, read or write to the daemon timed out, see [`Timeouts`](crate::docker::Timeouts).
+    Timeout,
 }
 
 impl Error {
@@ -34,24 +39,127 @@ impl Error {
             inner: ErrorImpl {
                 kind,
                 error: error.into(),
+                unanswered: false,
             },
         }
     }
 
-    pub(crate) fn docker_api_response(
-        status: u16,
-        inner: shiprs_models::models::ErrorResponse,
-    ) -> Error {
-        Error::new(
-            ErrorKind::DockerApiResponse,
-            DockerApiResponse { status, inner },
-        )
+    /// Mark the error as raised before any of the response was received, e.g. because the
+    /// connection could not be opened or was closed by the daemon.
+    pub(crate) fn unanswered(mut self) -> Error {
+        self.inner.unanswered = true;
+        self
+    }
+
+    pub(crate) fn docker_api_response(response: DockerApiResponse) -> Error {
+        Error::new(ErrorKind::DockerApiResponse, response)
+    }
+
+    fn io(err: std::io::Error) -> Error {
+        // Errors of the HTTP layer, e.g. raised while writing a request, are carried through
+        // `std::io::Error`.
+        if err.get_ref().is_some_and(|err| err.is::<HttpError>()) {
+            return Error::http(err.into());
+        }
+        match err.kind() {
+            std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock => {
+                Error::new(ErrorKind::Timeout, err)
+            }
+            _ => Error::new(ErrorKind::Io, err),
+        }
+    }
+
+    fn http(err: HttpError) -> Error {
+        let timed_out = err
+            .source()
+            .and_then(|source| source.downcast_ref::<std::io::Error>())
+            .is_some_and(|source| {
+                matches!(
+                    source.kind(),
+                    std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock
+                )
+            });
+
+        match timed_out {
+            true => Error::new(ErrorKind::Timeout, err),
+            false => Error::new(ErrorKind::ShiprsHttp, err),
+        }
+    }
+
+    /// An error reported by the daemon in the middle of a streamed response,
+    /// after a successful status code has already been sent.
+    pub(crate) fn docker_stream<S: Into<String>>(message: S) -> Error {
+        Error::new(ErrorKind::DockerStream, message.into())
     }
 
     pub fn kind(&self) -> ErrorKind {
         self.inner.kind
     }
 
+    /// The HTTP status code of the response, if the error was returned by the docker API.
+    pub fn status(&self) -> Option<u16> {
+        self.api_response().map(|response| response.status)
+    }
+
+    /// The error returned by the docker API, if any.
+    pub fn api_response(&self) -> Option<&DockerApiResponse> {
+        match self.inner.kind {
+            ErrorKind::DockerApiResponse => self.inner.error.downcast_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the object the request refers to does not exist (`404 Not Found`).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    ///
+    /// // Remove the container if it exists.
+    /// match docker.containers().get("my_container").remove(None) {
+    ///     Err(err) if err.is_not_found() => {}
+    ///     res => res?,
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(404)
+    }
+
+    /// Returns `true` if the request conflicts with the state of the daemon (`409 Conflict`),
+    /// e.g. when creating an object whose name is already in use.
+    pub fn is_conflict(&self) -> bool {
+        self.status() == Some(409)
+    }
+
+    /// Returns `true` if the object was already in the requested state (`304 Not Modified`).
+    /// The methods changing the state of a container report it as [`StateChange::AlreadyInState`](crate::container::StateChange) instead.
+    pub fn is_not_modified(&self) -> bool {
+        self.status() == Some(304)
+    }
+
+    /// Returns `true` if the credentials were missing or rejected (`401 Unauthorized`).
+    pub fn is_unauthorized(&self) -> bool {
+        self.status() == Some(401)
+    }
+
+    /// Returns `true` if the daemon failed to process a valid request (`5xx`).
+    pub fn is_server_error(&self) -> bool {
+        matches!(self.status(), Some(500..=599))
+    }
+
+    /// Returns `true` if the connection to the daemon failed before any of the response was
+    /// received, e.g. because the daemon restarted, in which case sending the request again may
+    /// succeed.
+    pub(crate) fn is_connection_error(&self) -> bool {
+        self.inner.unanswered
+    }
+
     pub fn into_inner(self) -> Box<dyn StdError + Send + Sync> {
         self.inner.error
     }
@@ -78,10 +186,17 @@ impl StdError for Error {
     }
 }
 
+/// An error response of the docker API.
 #[derive(Debug)]
 pub struct DockerApiResponse {
+    /// The error, as described by the daemon. When the body is not a JSON `ErrorResponse`,
+    /// e.g. a plain-text message from a proxy, the message is the body itself,
+    /// or the reason phrase of the status line if the body is empty.
     pub inner: shiprs_models::models::ErrorResponse,
     pub status: u16,
+    pub headers: shiprs_http::HeaderMap,
+    /// The raw body of the response.
+    pub body: Vec<u8>,
 }
 
 impl std::fmt::Display for DockerApiResponse {
@@ -95,10 +210,10 @@ impl StdError for DockerApiResponse {}
 use macros::error_from;
 
 error_from! {
-    std::io::Error => Io;
+    std::io::Error => fn io;
     SerdeJsonError => SerdeJson;
     serde_urlencoded::ser::Error => SerdeUrlEncoded;
-    HttpError => ShiprsHttp;
+    HttpError => fn http;
 }
 
 mod macros {
@@ -126,3 +241,127 @@ mod macros {
 
     pub(super) use error_from;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{json_response, MockDaemon};
+
+    #[test]
+    fn classify_api_errors() {
+        let daemon = MockDaemon::start([
+            json_response(404, r#"{"message":"No such container: web"}"#),
+            json_response(
+                409,
+                r#"{"message":"Conflict. The container name \"/web\" is already in use"}"#,
+            ),
+            json_response(500, r#"{"message":"driver failed"}"#),
+        ]);
+        let docker = daemon.docker();
+        let container = docker.containers().get("web");
+
+        let err = container.inspect(None).unwrap_err();
+        assert!(err.is_not_found());
+        assert_eq!(err.status(), Some(404));
+        assert_eq!(err.to_string(), "No such container: web");
+
+        let err = container.rename(crate::container::RenameOption { name: "web" });
+        assert!(err.unwrap_err().is_conflict());
+
+        let err = container.stop(None).unwrap_err();
+        assert!(err.is_server_error());
+        assert!(!err.is_not_found());
+    }
+
+    #[test]
+    fn keep_non_json_error_bodies() {
+        let daemon = MockDaemon::start([
+            "HTTP/1.1 403 Forbidden\r\nContent-Type: text/plain\r\nContent-Length: 21\r\n\r\nAccess denied (proxy)",
+            "HTTP/1.1 502 Bad Gateway\r\nContent-Length: 0\r\n\r\n",
+            "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 14\r\n\r\n{\"message\":\"dr",
+        ]);
+        let docker = daemon.docker();
+        let container = docker.containers().get("web");
+
+        let err = container.inspect(None).unwrap_err();
+        assert_eq!(err.status(), Some(403));
+        assert_eq!(err.to_string(), "Access denied (proxy)");
+        let response = err.api_response().unwrap();
+        assert_eq!(response.headers.content_type(), Some("text/plain"));
+        assert_eq!(response.body, b"Access denied (proxy)");
+
+        let err = container.inspect(None).unwrap_err();
+        assert!(err.is_server_error());
+        assert_eq!(err.to_string(), "Bad Gateway");
+        assert!(err.api_response().unwrap().body.is_empty());
+
+        let err = container.inspect(None).unwrap_err();
+        assert_eq!(err.status(), Some(500));
+        assert_eq!(err.api_response().unwrap().body, br#"{"message":"dr"#);
+    }
+
+    #[test]
+    fn time_out_slow_requests() {
+        use std::time::Duration;
+
+        use crate::docker::Timeouts;
+
+        let daemon = MockDaemon::start([
+            // The body is never completed, so the client waits for the rest of it.
+            "HTTP/1.1 200 OK\r\nContent-Length: 20\r\n\r\n{\"Id\":".to_string(),
+            json_response(200, r#"{"Id":"web"}"#),
+        ]);
+        let docker = daemon.docker();
+        let timeouts = Timeouts {
+            read: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+
+        let err = docker
+            .with_timeouts(timeouts)
+            .containers()
+            .get("web")
+            .inspect(None)
+            .unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::Timeout));
+
+        // The connection left in the middle of a response is replaced.
+        let container = docker.containers().get("web").inspect(None).unwrap();
+        assert_eq!(container.id, Some("web".to_string()));
+    }
+
+    #[test]
+    fn time_out_idle_streams() {
+        use std::time::Duration;
+
+        use crate::docker::Timeouts;
+
+        let mut response = b"HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\n".to_vec();
+        response.extend_from_slice(&[1, 0, 0, 0, 0, 0, 0, 3]);
+        response.extend_from_slice(b"up\n");
+        let daemon = MockDaemon::start([response]);
+
+        let timeouts = Timeouts {
+            stream_idle: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        let docker = daemon.docker().with_timeouts(timeouts);
+        let mut logs = docker.services().get("web").logs(None).unwrap();
+
+        assert_eq!(
+            logs.next().unwrap().unwrap(),
+            crate::stream::LogOutput::StdOut(b"up\n".to_vec())
+        );
+        let err = logs.next().unwrap().unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::Timeout));
+    }
+
+    #[test]
+    fn classify_other_errors() {
+        let err = Error::from(std::io::Error::from(std::io::ErrorKind::BrokenPipe));
+
+        assert_eq!(err.status(), None);
+        assert!(!err.is_not_found());
+        assert!(!err.is_server_error());
+    }
+}
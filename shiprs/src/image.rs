@@ -1 +1,408 @@
+use std::io::Read;
 
+use serde::Serialize;
+use shiprs_http::uri::PathTemplate;
+use shiprs_http::RequestBuilder;
+use shiprs_models::models::*;
+
+use crate::docker::Docker;
+use crate::error::{Error, Result};
+use crate::stream::BodyStream;
+use crate::Filter;
+
+/// Interface for interacting with docker images.
+///
+/// # Example
+/// ```no_run
+/// # use shiprs::error::Result;
+/// use shiprs::Docker;
+///
+/// # fn main() -> Result<()> {
+/// let docker = Docker::new()?;
+///
+/// let tarball = std::fs::File::open("images.tar")?;
+/// let loaded = docker.images().import(tarball, true)?;
+///
+/// for image in loaded {
+///    println!("Loaded: {}", image);
+/// }
+///
+/// # Ok(())
+/// # }
+/// ```
+pub struct Images<'docker> {
+    docker: &'docker Docker,
+}
+
+impl<'docker> Images<'docker> {
+    pub(crate) fn new(docker: &'docker Docker) -> Self {
+        Images { docker }
+    }
+
+    /// Export several images.
+    /// This corresponds to the `GET /images/get` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Image/operation/ImageGetAll) for more information.
+    ///
+    /// # Description
+    /// Get a tarball containing all images and metadata for several image repositories.
+    /// For each value of the `names` parameter: if it is a specific name and tag (e.g. `ubuntu:latest`),
+    /// then only that image (and its parents) are returned; if it is an image ID, similarly only that image (and its parents) are returned
+    /// and there would be no names referenced in the `repositories` file for this image ID.
+    ///
+    /// The tarball is streamed from the daemon as it is read, so it is never held in memory as a whole.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use std::fs::File;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    ///
+    /// let mut tarball = docker
+    ///     .images()
+    ///     .export(&["hello-world:linux", "alpine:latest"])?;
+    ///
+    /// std::io::copy(&mut tarball, &mut File::create("images.tar")?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn export<T>(&self, names: &[T]) -> Result<BodyStream>
+    where
+        T: AsRef<str>,
+    {
+        let url = "/images/get";
+        let query = names
+            .iter()
+            .map(|name| ("names", name.as_ref()))
+            .collect::<Vec<_>>();
+        let request = RequestBuilder::<Vec<(&str, &str)>, ()>::get(url)
+            .query(Some(query))
+            .build()?;
+
+        self.docker.process_into_stream(request)
+    }
+
+    /// Import images.
+    /// This corresponds to the `POST /images/load` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Image/operation/ImageLoad) for more information.
+    ///
+    /// # Description
+    /// Load a set of images and tags into a repository from a tarball, as produced by [`Images::export`].
+    /// The tarball is streamed to the daemon as it is read from `tar`, so it is never held in memory as a whole.
+    ///
+    /// Returns the references of the loaded images, i.e. `repository:tag` for tagged images and the image ID otherwise.
+    /// Set `quiet` to suppress the progress details sent by the daemon during the load.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use std::fs::File;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    ///
+    /// let loaded = docker
+    ///     .images()
+    ///     .import(File::open("images.tar")?, true)?;
+    ///
+    /// println!("{:?}", loaded);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn import<R>(&self, tar: R, quiet: bool) -> Result<Vec<String>>
+    where
+        R: Read,
+    {
+        let url = "/images/load";
+        let request = RequestBuilder::<Vec<(&str, bool)>, ()>::post(url)
+            .query(Some(vec![("quiet", quiet)]))
+            .stream("application/x-tar", tar)
+            .build()?;
+
+        let stream = self.docker.process_into_stream(request)?;
+
+        let mut loaded = Vec::new();
+        for message in serde_json::Deserializer::from_reader(stream).into_iter::<BuildInfo>() {
+            let message = message?;
+            if let Some(error) = message.error {
+                return Err(Error::docker_stream(error));
+            }
+
+            let reference = message.stream.as_deref().and_then(|stream| {
+                stream
+                    .strip_prefix("Loaded image: ")
+                    .or_else(|| stream.strip_prefix("Loaded image ID: "))
+            });
+            if let Some(reference) = reference {
+                loaded.push(reference.trim_end().to_string());
+            }
+        }
+
+        Ok(loaded)
+    }
+
+    /// Search images.
+    /// This corresponds to the `GET /images/search` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Image/operation/ImageSearch) for more information.
+    ///
+    /// # Description
+    /// Search for an image on Docker Hub, or on the registry given in the `term` (e.g. `localhost:5000/alpine`).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::image::{SearchFilter, SearchOption};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let options = SearchOption {
+    ///     limit: Some(10),
+    ///     filters: vec![SearchFilter::IsOfficial(true), SearchFilter::Stars(100)],
+    /// };
+    ///
+    /// let images = docker.images().search("alpine", Some(options))?;
+    ///
+    /// for image in images {
+    ///     println!("{:?}: {:?}", image.name, image.description);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn search<T>(
+        &self,
+        term: T,
+        options: Option<SearchOption>,
+    ) -> Result<Vec<ImageSearchResponseItem>>
+    where
+        T: AsRef<str>,
+    {
+        let url = "/images/search";
+        let query = SearchQuery {
+            term: term.as_ref(),
+            options: options.unwrap_or_default(),
+        };
+        let request = RequestBuilder::<SearchQuery, ()>::get(url)
+            .query(Some(query))
+            .build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// Get image information from the registry.
+    /// This corresponds to the `GET /distribution/(name)/json` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Distribution/operation/DistributionInspect) for more information.
+    ///
+    /// # Description
+    /// Return image digest and platform information by contacting the registry, without pulling the image.
+    /// The `credentials` are only needed for private registries.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    ///
+    /// let distribution = docker
+    ///     .images()
+    ///     .distribution_inspect("alpine:latest", None)?;
+    ///
+    /// let supports_arm64 = distribution
+    ///     .platforms
+    ///     .iter()
+    ///     .any(|p| p.os.as_deref() == Some("linux") && p.architecture.as_deref() == Some("arm64"));
+    /// println!("linux/arm64 supported: {}", supports_arm64);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn distribution_inspect<T>(
+        &self,
+        name: T,
+        credentials: Option<AuthConfig>,
+    ) -> Result<DistributionInspect>
+    where
+        T: AsRef<str>,
+    {
+        let url = PathTemplate::new("/distribution/{}/json")
+            .segment(name.as_ref())
+            .build()?;
+        let mut request = RequestBuilder::<(), ()>::get(&url);
+        if let Some(credentials) = credentials {
+            request = request.header("X-Registry-Auth", crate::registry_auth(&credentials)?);
+        }
+
+        let request = request.build()?;
+        self.docker.process_into_value(request)
+    }
+}
+
+/// Options for the [`search`](Images::search) method.
+/// This struct corresponds to the param options of the `GET /images/search` endpoint.
+/// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Image/operation/ImageSearch) for more information.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SearchOption {
+    /// Maximum number of results to return.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+
+    /// Filters to process on the search results.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(serialize_with = "crate::serialize_filters")]
+    pub filters: Vec<SearchFilter>,
+}
+
+/// Filters available for the [`search`](Images::search) method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchFilter {
+    /// Only keep the official images, or the non official ones.
+    IsOfficial(bool),
+    /// Only keep the images with automated builds enabled, or disabled.
+    IsAutomated(bool),
+    /// Only keep the images with at least this number of stars.
+    Stars(u64),
+}
+
+impl Filter for SearchFilter {
+    fn key(&self) -> &'static str {
+        match self {
+            SearchFilter::IsOfficial(_) => "is-official",
+            SearchFilter::IsAutomated(_) => "is-automated",
+            SearchFilter::Stars(_) => "stars",
+        }
+    }
+
+    fn value(&self) -> String {
+        match self {
+            SearchFilter::IsOfficial(official) => official.to_string(),
+            SearchFilter::IsAutomated(automated) => automated.to_string(),
+            SearchFilter::Stars(stars) => stars.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SearchQuery<'a> {
+    term: &'a str,
+    #[serde(flatten)]
+    options: SearchOption,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{json_response, MockDaemon};
+
+    #[test]
+    fn search_images() -> Result<()> {
+        let daemon = MockDaemon::start([json_response(
+            200,
+            r#"[{"description":"A minimal Docker image","is_official":true,"is_automated":false,"name":"alpine","star_count":10000}]"#,
+        )]);
+
+        let options = SearchOption {
+            limit: Some(5),
+            filters: vec![
+                SearchFilter::IsOfficial(true),
+                SearchFilter::Stars(100),
+                SearchFilter::IsAutomated(false),
+            ],
+        };
+        let images = daemon.docker().images().search("alpine", Some(options))?;
+
+        let request = daemon.request();
+        assert_eq!(
+            request.line,
+            "GET /v1.44/images/search?term=alpine&limit=5&filters=%7B%22is-automated%22%3A%5B%22false%22%5D%2C%22is-official%22%3A%5B%22true%22%5D%2C%22stars%22%3A%5B%22100%22%5D%7D HTTP/1.1"
+        );
+
+        assert_eq!(
+            images,
+            vec![ImageSearchResponseItem {
+                description: Some("A minimal Docker image".to_string()),
+                is_official: Some(true),
+                is_automated: Some(false),
+                name: Some("alpine".to_string()),
+                star_count: Some(10000),
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn distribution_inspect() -> Result<()> {
+        let daemon = MockDaemon::start([json_response(
+            200,
+            r#"{"Descriptor":{"mediaType":"application/vnd.oci.image.index.v1+json","digest":"sha256:c5b1261d","size":1638},"Platforms":[{"architecture":"amd64","os":"linux"},{"architecture":"arm64","os":"linux","variant":"v8"}]}"#,
+        )]);
+
+        let credentials = AuthConfig {
+            username: Some("shiprs".to_string()),
+            password: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let distribution = daemon
+            .docker()
+            .images()
+            .distribution_inspect("localhost:5000/alpine:latest", Some(credentials))?;
+
+        let request = daemon.request();
+        assert_eq!(
+            request.line,
+            "GET /v1.44/distribution/localhost:5000%2Falpine:latest/json HTTP/1.1"
+        );
+        assert_eq!(
+            request.header("X-Registry-Auth"),
+            Some("eyJ1c2VybmFtZSI6InNoaXBycyIsInBhc3N3b3JkIjoic2VjcmV0In0=")
+        );
+
+        assert_eq!(distribution.descriptor.size, Some(1638));
+        assert!(distribution.platforms.iter().any(|platform| {
+            platform.os.as_deref() == Some("linux")
+                && platform.architecture.as_deref() == Some("arm64")
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn search_images_without_options() -> Result<()> {
+        let daemon = MockDaemon::start([json_response(200, "[]")]);
+
+        let images = daemon.docker().images().search("alpine", None)?;
+
+        assert_eq!(
+            daemon.request().line,
+            "GET /v1.44/images/search?term=alpine HTTP/1.1"
+        );
+        assert!(images.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn import_streams_tar_in_chunks() -> Result<()> {
+        let daemon = MockDaemon::start([json_response(
+            200,
+            r#"{"stream":"Loaded image: alpine:latest\n"}"#,
+        )]);
+
+        let tar: &[u8] = b"not really a tar archive";
+        let loaded = daemon.docker().images().import(tar, true)?;
+
+        let request = daemon.request();
+        assert_eq!(request.line, "POST /v1.44/images/load?quiet=true HTTP/1.1");
+        assert_eq!(request.header("Content-Type"), Some("application/x-tar"));
+        assert_eq!(request.header("Transfer-Encoding"), Some("chunked"));
+        assert_eq!(request.header("Content-Length"), None);
+        assert_eq!(request.body, tar);
+        assert_eq!(loaded, vec!["alpine:latest"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,316 @@
+//! Hooks called around every request sent to the daemon, e.g. to log requests, collect metrics or
+//! add headers.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use shiprs_http::{Method, Request, Response};
+
+use crate::error::Error;
+
+/// A hook called around every request sent by a [`Docker`](crate::Docker) client, registered with
+/// [`DockerBuilder::interceptor`](crate::docker::DockerBuilder::interceptor).
+///
+/// Interceptors are called in the order they are registered.
+///
+/// # Example
+/// ```no_run
+/// # use shiprs::error::Result;
+/// use std::sync::Arc;
+/// use shiprs::docker::Request;
+/// use shiprs::interceptor::{Exchange, Interceptor};
+/// use shiprs::Docker;
+///
+/// struct SlowRequests;
+///
+/// impl Interceptor for SlowRequests {
+///     fn before_request(&self, request: &mut Request<'_>) {
+///         request.headers_mut().insert("X-Request-Origin", "deploy-bot");
+///     }
+///
+///     fn after_response(&self, exchange: &Exchange<'_>) {
+///         if exchange.elapsed.as_secs() >= 1 {
+///             eprintln!("{} {} took {:?}", exchange.method, exchange.uri, exchange.elapsed);
+///         }
+///     }
+/// }
+///
+/// # fn main() -> Result<()> {
+/// let docker = Docker::builder().interceptor(Arc::new(SlowRequests)).build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait Interceptor: Send + Sync {
+    /// Called before `request` is sent, e.g. to add headers.
+    fn before_request(&self, request: &mut Request<'_>) {
+        let _ = request;
+    }
+
+    /// Called once the response is received or the request failed.
+    ///
+    /// For most requests the whole response, body included, has been read. For streamed
+    /// responses, such as logs or events, only the head has: the body is read afterwards by the
+    /// caller.
+    ///
+    /// Responses with a `3xx`, `4xx` or `5xx` status are given here as responses, before they are
+    /// turned into errors.
+    fn after_response(&self, exchange: &Exchange<'_>) {
+        let _ = exchange;
+    }
+}
+
+/// A request sent to the daemon and its outcome, given to [`Interceptor::after_response`].
+#[derive(Debug)]
+pub struct Exchange<'a> {
+    pub method: Method,
+
+    /// The endpoint of the request, e.g. `/containers/{}/json`, see
+    /// [`Endpoint`](shiprs_http::uri::Endpoint).
+    pub endpoint: &'a str,
+
+    /// The path and query the request was sent to.
+    pub uri: &'a str,
+
+    /// The time between sending the request and receiving the response, including reading its
+    /// body. For streamed responses, it stops once the head is received, since the body is read
+    /// afterwards.
+    pub elapsed: Duration,
+
+    /// The response, or the error that prevented receiving it.
+    pub result: Result<&'a Response, &'a Error>,
+}
+
+impl Exchange<'_> {
+    /// Whether the request failed, either with an error or a response which is neither `2xx` nor
+    /// `304 Not Modified`.
+    ///
+    /// A `304 Not Modified` response means that the object was already in the requested state,
+    /// e.g. when starting a running container, which is reported as
+    /// [`StateChange::AlreadyInState`](crate::container::StateChange::AlreadyInState).
+    pub fn is_failure(&self) -> bool {
+        match self.result {
+            Ok(res) => !matches!(res.status(), 200..=299 | 304),
+            Err(_) => true,
+        }
+    }
+}
+
+/// An interceptor counting the requests sent to each endpoint.
+///
+/// # Example
+/// ```no_run
+/// # use shiprs::error::Result;
+/// use std::sync::Arc;
+/// use shiprs::interceptor::Metrics;
+/// use shiprs::Docker;
+///
+/// # fn main() -> Result<()> {
+/// let metrics = Arc::new(Metrics::default());
+/// let docker = Docker::builder().interceptor(metrics.clone()).build()?;
+///
+/// docker.containers().list::<String>(None)?;
+///
+/// for ((method, endpoint), counters) in metrics.snapshot() {
+///     println!(
+///         "{} {}: {} requests, {} failures, {:?} on average",
+///         method,
+///         endpoint,
+///         counters.requests,
+///         counters.failures,
+///         counters.average_latency()
+///     );
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct Metrics {
+    endpoints: Mutex<HashMap<(Method, String), Counters>>,
+}
+
+impl Metrics {
+    /// The counters of `endpoint`, if a request has been sent to it.
+    pub fn get(&self, method: Method, endpoint: &str) -> Option<Counters> {
+        let endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        endpoints.get(&(method, endpoint.to_string())).copied()
+    }
+
+    /// The counters of every endpoint a request has been sent to.
+    pub fn snapshot(&self) -> HashMap<(Method, String), Counters> {
+        self.endpoints
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Reset every counter.
+    pub fn reset(&self) {
+        self.endpoints
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+impl Interceptor for Metrics {
+    fn after_response(&self, exchange: &Exchange<'_>) {
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        let counters = endpoints
+            .entry((exchange.method, exchange.endpoint.to_string()))
+            .or_default();
+
+        counters.requests += 1;
+        if exchange.is_failure() {
+            counters.failures += 1;
+        }
+        counters.total_latency += exchange.elapsed;
+        counters.max_latency = counters.max_latency.max(exchange.elapsed);
+    }
+}
+
+/// The counters of an endpoint, collected by [`Metrics`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Counters {
+    pub requests: u64,
+
+    /// The requests which failed, see [`Exchange::is_failure`].
+    pub failures: u64,
+
+    pub total_latency: Duration,
+
+    pub max_latency: Duration,
+}
+
+impl Counters {
+    pub fn average_latency(&self) -> Duration {
+        match self.requests {
+            0 => Duration::ZERO,
+            n => Duration::from_nanos((self.total_latency.as_nanos() / n as u128) as u64),
+        }
+    }
+}
+
+/// An interceptor logging every request with the [`log`](https://docs.rs/log) crate, under the
+/// `shiprs` target.
+///
+/// Responses are logged at the `debug` level, and requests which failed without a response at the
+/// `warn` level.
+///
+/// # Example
+/// ```no_run
+/// # use shiprs::error::Result;
+/// use std::sync::Arc;
+/// use shiprs::interceptor::LogInterceptor;
+/// use shiprs::Docker;
+///
+/// # fn main() -> Result<()> {
+/// let docker = Docker::builder().interceptor(Arc::new(LogInterceptor)).build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "log")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogInterceptor;
+
+#[cfg(feature = "log")]
+impl Interceptor for LogInterceptor {
+    fn after_response(&self, exchange: &Exchange<'_>) {
+        match exchange.result {
+            Ok(res) => log::debug!(
+                target: "shiprs",
+                "{} {} -> {} {} in {:?}",
+                exchange.method,
+                exchange.uri,
+                res.status(),
+                res.reason(),
+                exchange.elapsed
+            ),
+            Err(err) => log::warn!(
+                target: "shiprs",
+                "{} {} failed after {:?}: {}",
+                exchange.method,
+                exchange.uri,
+                exchange.elapsed,
+                err
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::container::StateChange;
+    use crate::mock::{json_response, MockDaemon};
+
+    struct Origin;
+
+    impl Interceptor for Origin {
+        fn before_request(&self, request: &mut Request<'_>) {
+            request.headers_mut().insert("X-Request-Origin", "tests");
+        }
+    }
+
+    #[test]
+    fn intercept_requests() -> crate::error::Result<()> {
+        let daemon = MockDaemon::start([
+            json_response(200, r#"{"Id":"4fa6e0f0c678"}"#),
+            json_response(200, r#"{"Id":"9c3b6f2b3d52"}"#),
+            json_response(404, r#"{"message":"No such container: db"}"#),
+            "HTTP/1.1 304 Not Modified\r\n\r\n".to_string(),
+        ]);
+        let metrics = Arc::new(Metrics::default());
+        let docker = daemon
+            .builder()
+            .interceptor(Arc::new(Origin))
+            .interceptor(metrics.clone())
+            .build()?;
+
+        docker.containers().get("web").inspect(None)?;
+        docker.containers().get("api").inspect(None)?;
+        let err = docker.containers().get("db").inspect(None).unwrap_err();
+        assert!(err.is_not_found());
+        let change = docker.containers().get("web").start(None)?;
+        assert_eq!(change, StateChange::AlreadyInState);
+        let err = docker.containers().get("web").inspect(None).unwrap_err();
+        assert!(err.api_response().is_none());
+
+        let request = daemon.request();
+        assert_eq!(request.line, "GET /v1.44/containers/web/json HTTP/1.1");
+        assert_eq!(request.header("X-Request-Origin"), Some("tests"));
+
+        let counters = metrics
+            .get(Method::Get, "/containers/{}/json")
+            .expect("no metrics for the inspected containers");
+        assert_eq!(counters.requests, 4);
+        assert_eq!(counters.failures, 2);
+        assert!(counters.max_latency <= counters.total_latency);
+
+        // Starting a running container is not a failure.
+        let counters = metrics
+            .get(Method::Post, "/containers/{}/start")
+            .expect("no metrics for the started container");
+        assert_eq!(counters.requests, 1);
+        assert_eq!(counters.failures, 0);
+        assert_eq!(metrics.snapshot().len(), 2);
+
+        metrics.reset();
+        assert_eq!(metrics.get(Method::Get, "/containers/{}/json"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn average_latency() {
+        let counters = Counters {
+            requests: 4,
+            total_latency: Duration::from_millis(10),
+            ..Default::default()
+        };
+        assert_eq!(counters.average_latency(), Duration::from_micros(2500));
+        assert_eq!(Counters::default().average_latency(), Duration::ZERO);
+    }
+}
@@ -1,12 +1,30 @@
+mod base64;
+pub mod build;
+pub mod config;
 pub mod container;
 pub mod docker;
 pub mod error;
-mod image;
+pub mod image;
+pub mod interceptor;
+#[cfg(test)]
+mod mock;
 mod network;
+pub mod node;
+pub mod plugin;
+pub mod secret;
+pub mod service;
+pub mod stream;
+pub mod swarm;
+pub mod task;
 mod transport;
 
 pub use docker::Docker;
 
+/// Encode the credentials sent to the daemon in the `X-Registry-Auth` header.
+pub(crate) fn registry_auth(auth: &shiprs_models::models::AuthConfig) -> error::Result<String> {
+    Ok(base64::encode_url(serde_json::to_vec(auth)?))
+}
+
 pub(crate) fn serialize_as_json<T: serde::Serialize, S: serde::Serializer>(
     t: &T,
     s: S,
@@ -15,3 +33,25 @@ pub(crate) fn serialize_as_json<T: serde::Serialize, S: serde::Serializer>(
         &serde_json::to_string(t).map_err(|e| serde::ser::Error::custom(e.to_string()))?,
     )
 }
+
+/// A typed filter, serialized along with the other filters of a request as a JSON encoded
+/// `map[string][]string`.
+pub(crate) trait Filter {
+    /// The name of the filter, e.g. `label`.
+    fn key(&self) -> &'static str;
+
+    /// The value to filter on.
+    fn value(&self) -> String;
+}
+
+pub(crate) fn serialize_filters<F: Filter, S: serde::Serializer>(
+    filters: &[F],
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    let mut map = std::collections::BTreeMap::<_, Vec<_>>::new();
+    for filter in filters {
+        map.entry(filter.key()).or_default().push(filter.value());
+    }
+
+    serialize_as_json(&map, s)
+}
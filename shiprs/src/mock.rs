@@ -0,0 +1,189 @@
+//! A stand-in docker daemon used to test the client without a running docker engine.
+
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::docker::DockerBuilder;
+use crate::Docker;
+
+/// A request received by the [`MockDaemon`].
+#[derive(Debug)]
+pub(crate) struct MockRequest {
+    /// The request line, e.g. `GET /containers/json HTTP/1.1`.
+    pub line: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl MockRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A local HTTP server listening on a unix socket, answering every request with the next
+/// canned response.
+pub(crate) struct MockDaemon {
+    path: PathBuf,
+    requests: Receiver<MockRequest>,
+}
+
+impl MockDaemon {
+    /// Start a daemon answering requests with `responses`, in order.
+    /// Each response is written as is, so it must be a complete HTTP response.
+    /// An empty response closes the connection instead, as a restarting daemon would, and a
+    /// response with a `Connection: close` header closes it once written.
+    pub fn start<I, R>(responses: I) -> MockDaemon
+    where
+        I: IntoIterator<Item = R>,
+        R: Into<Vec<u8>>,
+    {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let path = std::env::temp_dir().join(format!(
+            "shiprs-mock-{}-{}.sock",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let listener = UnixListener::bind(&path).expect("failed to bind mock daemon");
+        let responses = Arc::new(Mutex::new(
+            responses
+                .into_iter()
+                .map(Into::into)
+                .collect::<VecDeque<_>>(),
+        ));
+        let (sender, requests) = mpsc::channel();
+
+        thread::spawn(move || {
+            for socket in listener.incoming() {
+                let Ok(socket) = socket else { break };
+                let responses = responses.clone();
+                let sender = sender.clone();
+                thread::spawn(move || serve(socket, responses, sender));
+            }
+        });
+
+        MockDaemon { path, requests }
+    }
+
+    /// A [`Docker`] client connected to this daemon.
+    pub fn docker(&self) -> Docker {
+        self.builder()
+            .build()
+            .expect("failed to connect to mock daemon")
+    }
+
+    /// A builder of a [`Docker`] client connected to this daemon.
+    pub fn builder(&self) -> DockerBuilder {
+        Docker::builder().unix(self.path.to_str().unwrap())
+    }
+
+    /// The next request received by the daemon.
+    pub fn request(&self) -> MockRequest {
+        self.requests
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .expect("no request received by the mock daemon")
+    }
+}
+
+impl Drop for MockDaemon {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Build a response with a JSON body.
+pub(crate) fn json_response(status: u16, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+const CONNECTION_CLOSE: &[u8] = b"\r\nConnection: close\r\n";
+
+fn serve(
+    socket: UnixStream,
+    responses: Arc<Mutex<VecDeque<Vec<u8>>>>,
+    sender: Sender<MockRequest>,
+) {
+    let mut reader = BufReader::new(socket.try_clone().unwrap());
+    let mut writer = socket;
+
+    while let Some(request) = read_request(&mut reader) {
+        let _ = sender.send(request);
+
+        let Some(response) = responses.lock().unwrap().pop_front() else {
+            return;
+        };
+        if response.is_empty() || writer.write_all(&response).is_err() {
+            return;
+        }
+        if response
+            .windows(CONNECTION_CLOSE.len())
+            .any(|window| window == CONNECTION_CLOSE)
+        {
+            return;
+        }
+    }
+}
+
+fn read_request<R: BufRead>(reader: &mut R) -> Option<MockRequest> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).ok()? == 0 {
+        return None;
+    }
+
+    let mut headers = Vec::new();
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).ok()?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+
+        let (name, value) = header.split_once(':')?;
+        headers.push((name.to_string(), value.trim().to_string()));
+    }
+
+    let mut request = MockRequest {
+        line: line.trim_end().to_string(),
+        headers,
+        body: Vec::new(),
+    };
+
+    if request.header("Transfer-Encoding") == Some("chunked") {
+        loop {
+            let mut size = String::new();
+            reader.read_line(&mut size).ok()?;
+            let size = usize::from_str_radix(size.trim_end(), 16).ok()?;
+
+            let mut chunk = vec![0; size + 2];
+            reader.read_exact(&mut chunk).ok()?;
+            if size == 0 {
+                break;
+            }
+            request.body.extend_from_slice(&chunk[..size]);
+        }
+    } else if let Some(length) = request.header("Content-Length") {
+        let mut body = vec![0; length.parse().ok()?];
+        reader.read_exact(&mut body).ok()?;
+        request.body = body;
+    }
+
+    Some(request)
+}
@@ -0,0 +1,359 @@
+use serde::Serialize;
+
+use shiprs_http::uri::PathTemplate;
+use shiprs_http::RequestBuilder;
+use shiprs_models::models::{self, NodeSpec, NodeSpecRoleEnum};
+
+use crate::docker::Docker;
+use crate::error::Result;
+use crate::Filter;
+
+/// Interface for interacting with a swarm node.
+///
+/// # Example
+///
+/// ```no_run
+/// # use shiprs::error::Result;
+/// use shiprs::Docker;
+///
+/// # fn main() -> Result<()> {
+/// let docker = Docker::new()?;
+///
+/// let node = docker.nodes().get("insert node id here").inspect()?;
+///
+/// println!("{:?}", node.manager_status);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Node<'docker, T> {
+    docker: &'docker Docker,
+    id: T,
+}
+
+impl<'docker, T> Node<'docker, T>
+where
+    T: AsRef<str>,
+{
+    pub(crate) fn new(docker: &'docker Docker, id: T) -> Self {
+        Node { docker, id }
+    }
+
+    /// Inspect the node.
+    /// This corresponds to the `GET /nodes/(id)` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Node/operation/NodeInspect) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let node = docker.nodes().get("insert node id here").inspect()?;
+    /// println!("{:?}", node.description);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn inspect(&self) -> Result<models::Node> {
+        let url = PathTemplate::new("/nodes/{}")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<(), ()>::get(&url).build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// Update the node.
+    /// This corresponds to the `POST /nodes/(id)/update` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Node/operation/NodeUpdate) for more information.
+    ///
+    /// # Description
+    /// The `version` of the node being updated must be given in the options, as returned by [`Node::inspect`].
+    /// The given spec replaces the current one, so all the fields which should be kept must be set.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::node::UpdateOption;
+    /// use shiprs_models::models::NodeSpecAvailabilityEnum;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let node = docker.nodes().get("insert node id here");
+    ///
+    /// // Drain the node for maintenance.
+    /// let current = node.inspect()?;
+    /// let mut spec = current.spec.unwrap_or_default();
+    /// spec.availability = Some(NodeSpecAvailabilityEnum::DRAIN);
+    ///
+    /// let options = UpdateOption {
+    ///     version: current.version.and_then(|v| v.index).unwrap_or_default(),
+    /// };
+    /// node.update(spec, options)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn update(&self, spec: NodeSpec, options: UpdateOption) -> Result<()> {
+        let url = PathTemplate::new("/nodes/{}/update")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<UpdateOption, NodeSpec>::post(&url)
+            .query(Some(options))
+            .body(spec)
+            .build()?;
+
+        self.docker.process_into_unit(request)
+    }
+
+    /// Remove the node from the swarm.
+    /// This corresponds to the `DELETE /nodes/(id)` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Node/operation/NodeDelete) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::node::RemoveOption;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// docker
+    ///     .nodes()
+    ///     .get("insert node id here")
+    ///     .remove(Some(RemoveOption { force: true }))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remove(&self, options: Option<RemoveOption>) -> Result<()> {
+        let url = PathTemplate::new("/nodes/{}")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<RemoveOption, ()>::delete(&url)
+            .query(options)
+            .build()?;
+
+        self.docker.process_into_unit(request)
+    }
+}
+
+/// Interface for interacting with the swarm nodes.
+///
+/// # Example
+///
+/// ```no_run
+/// # use shiprs::error::Result;
+/// use shiprs::Docker;
+///
+/// # fn main() -> Result<()> {
+/// let docker = Docker::new()?;
+///
+/// for node in docker.nodes().list::<String>(None)? {
+///     println!("{:?}: {:?}", node.id, node.status);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Nodes<'docker> {
+    docker: &'docker Docker,
+}
+
+impl<'docker> Nodes<'docker> {
+    pub(crate) fn new(docker: &'docker Docker) -> Self {
+        Nodes { docker }
+    }
+
+    /// List the nodes.
+    /// This corresponds to the `GET /nodes` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Node/operation/NodeList) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::node::{ListFilter, ListOption};
+    /// use shiprs_models::models::NodeSpecRoleEnum;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let options = ListOption {
+    ///     filters: vec![ListFilter::Role(NodeSpecRoleEnum::WORKER)],
+    /// };
+    ///
+    /// let workers = docker.nodes().list::<String>(Some(options))?;
+    /// println!("{:?}", workers);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list<T>(&self, options: Option<ListOption<T>>) -> Result<Vec<models::Node>>
+    where
+        T: AsRef<str> + Serialize,
+    {
+        let url = "/nodes";
+        let request = RequestBuilder::<ListOption<T>, ()>::get(url)
+            .query(options)
+            .build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// Get a node by id or name.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let node = docker.nodes().get("insert node id here");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get<T>(self, id: T) -> Node<'docker, T>
+    where
+        T: AsRef<str>,
+    {
+        Node::new(self.docker, id)
+    }
+}
+
+/// Parameters used for the [List Nodes API](Nodes::list)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListOption<T>
+where
+    T: AsRef<str>,
+{
+    /// Filters to process on the node list.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(serialize_with = "crate::serialize_filters")]
+    pub filters: Vec<ListFilter<T>>,
+}
+
+/// Filters available for the [List Nodes API](Nodes::list).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListFilter<T>
+where
+    T: AsRef<str>,
+{
+    /// Only list the node with this ID.
+    Id(T),
+    /// Only list the nodes with this engine label, given as `key` or `key=value`.
+    Label(T),
+    /// Only list the nodes with this membership.
+    Membership(Membership),
+    /// Only list the nodes with this name.
+    Name(T),
+    /// Only list the nodes with this node label, given as `key` or `key=value`.
+    NodeLabel(T),
+    /// Only list the nodes with this role.
+    Role(NodeSpecRoleEnum),
+}
+
+/// The membership states a node can be filtered on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Membership {
+    Accepted,
+    Pending,
+}
+
+impl<T> Filter for ListFilter<T>
+where
+    T: AsRef<str>,
+{
+    fn key(&self) -> &'static str {
+        match self {
+            ListFilter::Id(_) => "id",
+            ListFilter::Label(_) => "label",
+            ListFilter::Membership(_) => "membership",
+            ListFilter::Name(_) => "name",
+            ListFilter::NodeLabel(_) => "node.label",
+            ListFilter::Role(_) => "role",
+        }
+    }
+
+    fn value(&self) -> String {
+        match self {
+            ListFilter::Id(value)
+            | ListFilter::Label(value)
+            | ListFilter::Name(value)
+            | ListFilter::NodeLabel(value) => value.as_ref().to_string(),
+            ListFilter::Membership(Membership::Accepted) => "accepted".to_string(),
+            ListFilter::Membership(Membership::Pending) => "pending".to_string(),
+            ListFilter::Role(role) => role.to_string(),
+        }
+    }
+}
+
+/// Options for the [`remove`](Node::remove) method.
+/// This struct corresponds to the param options of the `DELETE /nodes/(id)` endpoint.
+/// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Node/operation/NodeDelete) for more information.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct RemoveOption {
+    /// Force remove the node from the swarm, even if it is still reachable.
+    pub force: bool,
+}
+
+/// Options for the [`update`](Node::update) method.
+/// This struct corresponds to the param options of the `POST /nodes/(id)/update` endpoint.
+/// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Node/operation/NodeUpdate) for more information.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct UpdateOption {
+    /// The version number of the node object being updated.
+    /// This is required to avoid conflicting writes.
+    pub version: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{json_response, MockDaemon};
+    use shiprs_models::models::NodeSpecAvailabilityEnum;
+
+    #[test]
+    fn drain_node() -> Result<()> {
+        let daemon = MockDaemon::start(["HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n"]);
+
+        let spec = NodeSpec {
+            role: Some(NodeSpecRoleEnum::WORKER),
+            availability: Some(NodeSpecAvailabilityEnum::DRAIN),
+            ..Default::default()
+        };
+        daemon
+            .docker()
+            .nodes()
+            .get("node-1")
+            .update(spec, UpdateOption { version: 8 })?;
+
+        let request = daemon.request();
+        assert_eq!(
+            request.line,
+            "POST /v1.44/nodes/node-1/update?version=8 HTTP/1.1"
+        );
+        assert_eq!(request.body, br#"{"Role":"worker","Availability":"drain"}"#);
+
+        Ok(())
+    }
+
+    #[test]
+    fn list_nodes_with_filters() -> Result<()> {
+        let daemon = MockDaemon::start([json_response(200, "[]")]);
+
+        let options = ListOption {
+            filters: vec![
+                ListFilter::Role(NodeSpecRoleEnum::MANAGER),
+                ListFilter::Membership(Membership::Accepted),
+                ListFilter::NodeLabel("zone=eu"),
+            ],
+        };
+        daemon.docker().nodes().list(Some(options))?;
+
+        assert_eq!(
+            daemon.request().line,
+            "GET /v1.44/nodes?filters=%7B%22membership%22%3A%5B%22accepted%22%5D%2C%22node.label%22%3A%5B%22zone%3Deu%22%5D%2C%22role%22%3A%5B%22manager%22%5D%7D HTTP/1.1"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,636 @@
+use std::io::Read;
+
+use serde::Serialize;
+
+use shiprs_http::uri::PathTemplate;
+use shiprs_http::RequestBuilder;
+use shiprs_models::models::{self, AuthConfig, PluginPrivilege};
+
+use crate::docker::Docker;
+use crate::error::Result;
+use crate::Filter;
+
+/// Interface for interacting with a plugin.
+///
+/// # Example
+///
+/// ```no_run
+/// # use shiprs::error::Result;
+/// use shiprs::Docker;
+///
+/// # fn main() -> Result<()> {
+/// let docker = Docker::new()?;
+///
+/// let plugin = docker.plugins().get("vieux/sshfs:latest").inspect()?;
+///
+/// println!("{}: {}", plugin.name, plugin.enabled);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Plugin<'docker, T> {
+    docker: &'docker Docker,
+    name: T,
+}
+
+impl<'docker, T> Plugin<'docker, T>
+where
+    T: AsRef<str>,
+{
+    pub(crate) fn new(docker: &'docker Docker, name: T) -> Self {
+        Plugin { docker, name }
+    }
+
+    /// Inspect the plugin.
+    /// This corresponds to the `GET /plugins/(name)/json` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Plugin/operation/PluginInspect) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let plugin = docker.plugins().get("vieux/sshfs:latest").inspect()?;
+    /// println!("{:?}", plugin.settings);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn inspect(&self) -> Result<models::Plugin> {
+        let url = PathTemplate::new("/plugins/{}/json")
+            .segment(self.name.as_ref())
+            .build()?;
+        let request = RequestBuilder::<(), ()>::get(&url).build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// Remove the plugin, returning its last known state.
+    /// This corresponds to the `DELETE /plugins/(name)` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Plugin/operation/PluginDelete) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::plugin::RemoveOption;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// docker
+    ///     .plugins()
+    ///     .get("vieux/sshfs:latest")
+    ///     .remove(Some(RemoveOption { force: true }))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remove(&self, options: Option<RemoveOption>) -> Result<models::Plugin> {
+        let url = PathTemplate::new("/plugins/{}")
+            .segment(self.name.as_ref())
+            .build()?;
+        let request = RequestBuilder::<RemoveOption, ()>::delete(&url)
+            .query(options)
+            .build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// Enable the plugin.
+    /// This corresponds to the `POST /plugins/(name)/enable` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Plugin/operation/PluginEnable) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::plugin::EnableOption;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// docker
+    ///     .plugins()
+    ///     .get("vieux/sshfs:latest")
+    ///     .enable(Some(EnableOption { timeout: 30 }))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn enable(&self, options: Option<EnableOption>) -> Result<()> {
+        let url = PathTemplate::new("/plugins/{}/enable")
+            .segment(self.name.as_ref())
+            .build()?;
+        let request = RequestBuilder::<EnableOption, ()>::post(&url)
+            .query(options)
+            .build()?;
+
+        self.docker.process_into_unit(request)
+    }
+
+    /// Disable the plugin.
+    /// This corresponds to the `POST /plugins/(name)/disable` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Plugin/operation/PluginDisable) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// docker.plugins().get("vieux/sshfs:latest").disable(None)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn disable(&self, options: Option<DisableOption>) -> Result<()> {
+        let url = PathTemplate::new("/plugins/{}/disable")
+            .segment(self.name.as_ref())
+            .build()?;
+        let request = RequestBuilder::<DisableOption, ()>::post(&url)
+            .query(options)
+            .build()?;
+
+        self.docker.process_into_unit(request)
+    }
+
+    /// Upgrade the plugin to the image of the `remote` reference.
+    /// This corresponds to the `POST /plugins/(name)/upgrade` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Plugin/operation/PluginUpgrade) for more information.
+    ///
+    /// # Description
+    /// The plugin must be disabled. The privileges required by the new version, as returned
+    /// by [`Plugins::privileges`], must be accepted by passing them along.
+    ///
+    /// The call returns once the upgrade is complete.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let remote = "vieux/sshfs:next";
+    ///
+    /// let privileges = docker.plugins().privileges(remote)?;
+    /// docker
+    ///     .plugins()
+    ///     .get("vieux/sshfs:latest")
+    ///     .upgrade(remote, privileges, None)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn upgrade<R>(
+        &self,
+        remote: R,
+        privileges: Vec<PluginPrivilege>,
+        credentials: Option<AuthConfig>,
+    ) -> Result<()>
+    where
+        R: AsRef<str> + Serialize,
+    {
+        let url = PathTemplate::new("/plugins/{}/upgrade")
+            .segment(self.name.as_ref())
+            .build()?;
+        let mut request = RequestBuilder::<[(&str, R); 1], Vec<PluginPrivilege>>::post(&url)
+            .query(Some([("remote", remote)]))
+            .body(privileges);
+        if let Some(credentials) = credentials {
+            request = request.header("X-Registry-Auth", crate::registry_auth(&credentials)?);
+        }
+
+        let stream = self.docker.process_into_stream(request.build()?)?;
+        crate::stream::wait_progress(stream)
+    }
+
+    /// Configure the plugin, with settings given as `KEY=value`.
+    /// This corresponds to the `POST /plugins/(name)/set` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Plugin/operation/PluginSet) for more information.
+    ///
+    /// # Description
+    /// Environment variables, mount sources, device paths and arguments declared as settable by the plugin can be set.
+    /// The plugin must be disabled.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// docker
+    ///     .plugins()
+    ///     .get("vieux/sshfs:latest")
+    ///     .set(&["DEBUG=1", "mymount.source=/var/lib/sshfs"])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set<S>(&self, settings: &[S]) -> Result<()>
+    where
+        S: AsRef<str> + Serialize,
+    {
+        let url = PathTemplate::new("/plugins/{}/set")
+            .segment(self.name.as_ref())
+            .build()?;
+        let request = RequestBuilder::<(), &[S]>::post(&url)
+            .body(settings)
+            .build()?;
+
+        self.docker.process_into_unit(request)
+    }
+
+    /// Push the plugin to its registry.
+    /// This corresponds to the `POST /plugins/(name)/push` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Plugin/operation/PluginPush) for more information.
+    ///
+    /// The call returns once the push is complete.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs_models::models::AuthConfig;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let credentials = AuthConfig {
+    ///     username: Some("storage".to_string()),
+    ///     password: Some("insert password here".to_string()),
+    ///     serveraddress: Some("registry.example.com".to_string()),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// docker
+    ///     .plugins()
+    ///     .get("registry.example.com/storage/volume:1.0")
+    ///     .push(Some(credentials))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn push(&self, credentials: Option<AuthConfig>) -> Result<()> {
+        let url = PathTemplate::new("/plugins/{}/push")
+            .segment(self.name.as_ref())
+            .build()?;
+        let mut request = RequestBuilder::<(), ()>::post(&url);
+        if let Some(credentials) = credentials {
+            request = request.header("X-Registry-Auth", crate::registry_auth(&credentials)?);
+        }
+
+        let stream = self.docker.process_into_stream(request.build()?)?;
+        crate::stream::wait_progress(stream)
+    }
+}
+
+/// Interface for interacting with the plugins.
+///
+/// # Example
+///
+/// ```no_run
+/// # use shiprs::error::Result;
+/// use shiprs::Docker;
+///
+/// # fn main() -> Result<()> {
+/// let docker = Docker::new()?;
+///
+/// for plugin in docker.plugins().list::<String>(None)? {
+///     println!("{}: {}", plugin.name, plugin.enabled);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Plugins<'docker> {
+    docker: &'docker Docker,
+}
+
+impl<'docker> Plugins<'docker> {
+    pub(crate) fn new(docker: &'docker Docker) -> Self {
+        Plugins { docker }
+    }
+
+    /// List the installed plugins.
+    /// This corresponds to the `GET /plugins` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Plugin/operation/PluginList) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::plugin::{ListFilter, ListOption};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let options = ListOption {
+    ///     filters: vec![ListFilter::Capability("volumedriver"), ListFilter::Enabled(true)],
+    /// };
+    ///
+    /// let plugins = docker.plugins().list(Some(options))?;
+    /// println!("{:?}", plugins);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list<T>(&self, options: Option<ListOption<T>>) -> Result<Vec<models::Plugin>>
+    where
+        T: AsRef<str> + Serialize,
+    {
+        let url = "/plugins";
+        let request = RequestBuilder::<ListOption<T>, ()>::get(url)
+            .query(options)
+            .build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// Get the privileges a plugin requires to be installed.
+    /// This corresponds to the `GET /plugins/privileges` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Plugin/operation/GetPluginPrivileges) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    ///
+    /// for privilege in docker.plugins().privileges("vieux/sshfs:latest")? {
+    ///     println!("{:?}: {:?}", privilege.name, privilege.value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn privileges<R>(&self, remote: R) -> Result<Vec<PluginPrivilege>>
+    where
+        R: AsRef<str> + Serialize,
+    {
+        let url = "/plugins/privileges";
+        let request = RequestBuilder::<[(&str, R); 1], ()>::get(url)
+            .query(Some([("remote", remote)]))
+            .build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// Pull and install a plugin. The plugin is disabled once installed.
+    /// This corresponds to the `POST /plugins/pull` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Plugin/operation/PluginPull) for more information.
+    ///
+    /// # Description
+    /// The privileges required by the plugin, as returned by [`Plugins::privileges`], must be accepted by passing them along.
+    /// The credentials are needed when the plugin is hosted on a private registry.
+    ///
+    /// The call returns once the plugin is installed.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::plugin::PullOption;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let options = PullOption {
+    ///     remote: "vieux/sshfs:latest",
+    ///     name: Some("sshfs"),
+    /// };
+    ///
+    /// let privileges = docker.plugins().privileges(options.remote)?;
+    /// docker.plugins().pull(options, privileges, None)?;
+    /// docker.plugins().get("sshfs").enable(None)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pull<T>(
+        &self,
+        options: PullOption<T>,
+        privileges: Vec<PluginPrivilege>,
+        credentials: Option<AuthConfig>,
+    ) -> Result<()>
+    where
+        T: AsRef<str> + Serialize,
+    {
+        let url = "/plugins/pull";
+        let mut request = RequestBuilder::<PullOption<T>, Vec<PluginPrivilege>>::post(url)
+            .query(Some(options))
+            .body(privileges);
+        if let Some(credentials) = credentials {
+            request = request.header("X-Registry-Auth", crate::registry_auth(&credentials)?);
+        }
+
+        let stream = self.docker.process_into_stream(request.build()?)?;
+        crate::stream::wait_progress(stream)
+    }
+
+    /// Create a plugin from a tarball.
+    /// This corresponds to the `POST /plugins/create` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Plugin/operation/PluginCreate) for more information.
+    ///
+    /// # Description
+    /// The tarball must contain the `config.json` of the plugin and its root filesystem in a `rootfs` directory.
+    /// It is streamed to the daemon as it is read from `tar`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use std::fs::File;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    ///
+    /// docker
+    ///     .plugins()
+    ///     .create("storage/volume:1.0", File::open("plugin.tar")?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create<N, R>(&self, name: N, tar: R) -> Result<()>
+    where
+        N: AsRef<str> + Serialize,
+        R: Read,
+    {
+        let url = "/plugins/create";
+        let request = RequestBuilder::<[(&str, N); 1], ()>::post(url)
+            .query(Some([("name", name)]))
+            .stream("application/x-tar", tar)
+            .build()?;
+
+        let mut stream = self.docker.process_into_stream(request)?;
+        std::io::copy(&mut stream, &mut std::io::sink())?;
+
+        Ok(())
+    }
+
+    /// Get a plugin by name.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let plugin = docker.plugins().get("vieux/sshfs:latest");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get<T>(self, name: T) -> Plugin<'docker, T>
+    where
+        T: AsRef<str>,
+    {
+        Plugin::new(self.docker, name)
+    }
+}
+
+/// Parameters used for the [List Plugins API](Plugins::list)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListOption<T>
+where
+    T: AsRef<str>,
+{
+    /// Filters to process on the plugin list.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(serialize_with = "crate::serialize_filters")]
+    pub filters: Vec<ListFilter<T>>,
+}
+
+/// Filters available for the [List Plugins API](Plugins::list).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListFilter<T>
+where
+    T: AsRef<str>,
+{
+    /// Only list the plugins with this capability, e.g. `volumedriver`.
+    Capability(T),
+    /// Only list the enabled plugins, or the disabled ones.
+    Enabled(bool),
+}
+
+impl<T> Filter for ListFilter<T>
+where
+    T: AsRef<str>,
+{
+    fn key(&self) -> &'static str {
+        match self {
+            ListFilter::Capability(_) => "capability",
+            ListFilter::Enabled(_) => "enable",
+        }
+    }
+
+    fn value(&self) -> String {
+        match self {
+            ListFilter::Capability(value) => value.as_ref().to_string(),
+            ListFilter::Enabled(value) => value.to_string(),
+        }
+    }
+}
+
+/// Options for the [`pull`](Plugins::pull) method.
+/// This struct corresponds to the param options of the `POST /plugins/pull` endpoint.
+/// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Plugin/operation/PluginPull) for more information.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PullOption<T>
+where
+    T: AsRef<str>,
+{
+    /// Remote reference of the plugin to pull, e.g. `vieux/sshfs:latest`.
+    pub remote: T,
+
+    /// Local name of the installed plugin. The remote reference is used by default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<T>,
+}
+
+/// Options for the [`remove`](Plugin::remove) method.
+/// This struct corresponds to the param options of the `DELETE /plugins/(name)` endpoint.
+/// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Plugin/operation/PluginDelete) for more information.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct RemoveOption {
+    /// Disable the plugin before removing it, even if it is in use.
+    pub force: bool,
+}
+
+/// Options for the [`enable`](Plugin::enable) method.
+/// This struct corresponds to the param options of the `POST /plugins/(name)/enable` endpoint.
+/// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Plugin/operation/PluginEnable) for more information.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct EnableOption {
+    /// Timeout in seconds to wait for the plugin to start.
+    pub timeout: u64,
+}
+
+/// Options for the [`disable`](Plugin::disable) method.
+/// This struct corresponds to the param options of the `POST /plugins/(name)/disable` endpoint.
+/// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Plugin/operation/PluginDisable) for more information.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct DisableOption {
+    /// Disable the plugin even if it is in use.
+    pub force: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+    use crate::mock::{json_response, MockDaemon};
+
+    #[test]
+    fn pull_plugin_with_privileges() -> Result<()> {
+        let daemon = MockDaemon::start([json_response(
+            200,
+            "{\"status\":\"Pulling from vieux/sshfs\"}\n{\"status\":\"Download complete\"}\n",
+        )]);
+
+        let options = PullOption {
+            remote: "vieux/sshfs:latest",
+            name: Some("sshfs"),
+        };
+        let privileges = vec![PluginPrivilege {
+            name: Some("network".to_string()),
+            description: None,
+            value: Some(vec!["host".to_string()]),
+        }];
+        daemon.docker().plugins().pull(options, privileges, None)?;
+
+        let request = daemon.request();
+        assert_eq!(
+            request.line,
+            "POST /v1.44/plugins/pull?remote=vieux%2Fsshfs%3Alatest&name=sshfs HTTP/1.1"
+        );
+        assert_eq!(request.body, br#"[{"Name":"network","Value":["host"]}]"#);
+
+        Ok(())
+    }
+
+    #[test]
+    fn pull_plugin_reports_stream_error() {
+        let daemon = MockDaemon::start([json_response(
+            200,
+            "{\"status\":\"Pulling from vieux/sshfs\"}\n{\"error\":\"manifest unknown\"}\n",
+        )]);
+
+        let options = PullOption {
+            remote: "vieux/sshfs:missing",
+            name: None,
+        };
+        let err = daemon
+            .docker()
+            .plugins()
+            .pull(options, Vec::new(), None)
+            .unwrap_err();
+
+        assert!(matches!(err.kind(), ErrorKind::DockerStream));
+        assert_eq!(err.to_string(), "manifest unknown");
+    }
+
+    #[test]
+    fn set_plugin_settings() -> Result<()> {
+        let daemon = MockDaemon::start(["HTTP/1.1 204 No Content\r\n\r\n"]);
+
+        daemon.docker().plugins().get("sshfs").set(&["DEBUG=1"])?;
+
+        let request = daemon.request();
+        assert_eq!(request.line, "POST /v1.44/plugins/sshfs/set HTTP/1.1");
+        assert_eq!(request.body, br#"["DEBUG=1"]"#);
+
+        Ok(())
+    }
+}
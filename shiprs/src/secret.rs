@@ -0,0 +1,337 @@
+use serde::Serialize;
+
+use shiprs_http::uri::PathTemplate;
+use shiprs_http::RequestBuilder;
+use shiprs_models::models::{self, IdResponse, SecretSpec};
+
+use crate::docker::Docker;
+use crate::error::Result;
+use crate::Filter;
+
+/// Interface for interacting with a swarm secret.
+///
+/// # Example
+///
+/// ```no_run
+/// # use shiprs::error::Result;
+/// use shiprs::Docker;
+///
+/// # fn main() -> Result<()> {
+/// let docker = Docker::new()?;
+///
+/// let secret = docker.secrets().get("insert secret id here").inspect()?;
+///
+/// println!("{:?}", secret.spec);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Secret<'docker, T> {
+    docker: &'docker Docker,
+    id: T,
+}
+
+impl<'docker, T> Secret<'docker, T>
+where
+    T: AsRef<str>,
+{
+    pub(crate) fn new(docker: &'docker Docker, id: T) -> Self {
+        Secret { docker, id }
+    }
+
+    /// Inspect the secret. The data of the secret is never returned.
+    /// This corresponds to the `GET /secrets/(id)` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Secret/operation/SecretInspect) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let secret = docker.secrets().get("insert secret id here").inspect()?;
+    /// println!("{:?}", secret.version);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn inspect(&self) -> Result<models::Secret> {
+        let url = PathTemplate::new("/secrets/{}")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<(), ()>::get(&url).build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// Update the secret.
+    /// This corresponds to the `POST /secrets/(id)/update` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Secret/operation/SecretUpdate) for more information.
+    ///
+    /// # Description
+    /// The `version` of the secret being updated must be given in the options, as returned by [`Secret::inspect`].
+    /// Only the labels of a secret can be updated, the other fields must be left unchanged.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::secret::UpdateOption;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let secret = docker.secrets().get("insert secret id here");
+    ///
+    /// let current = secret.inspect()?;
+    /// let mut spec = current.spec.unwrap_or_default();
+    /// spec.labels = Some([("rotated".to_string(), "true".to_string())].into());
+    ///
+    /// let options = UpdateOption {
+    ///     version: current.version.and_then(|v| v.index).unwrap_or_default(),
+    /// };
+    /// secret.update(spec, options)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn update(&self, spec: SecretSpec, options: UpdateOption) -> Result<()> {
+        let url = PathTemplate::new("/secrets/{}/update")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<UpdateOption, SecretSpec>::post(&url)
+            .query(Some(options))
+            .body(spec)
+            .build()?;
+
+        self.docker.process_into_unit(request)
+    }
+
+    /// Remove the secret.
+    /// This corresponds to the `DELETE /secrets/(id)` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Secret/operation/SecretDelete) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// docker.secrets().get("insert secret id here").remove()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remove(&self) -> Result<()> {
+        let url = PathTemplate::new("/secrets/{}")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<(), ()>::delete(&url).build()?;
+
+        self.docker.process_into_unit(request)
+    }
+}
+
+/// Interface for interacting with the swarm secrets.
+///
+/// # Example
+///
+/// ```no_run
+/// # use shiprs::error::Result;
+/// use shiprs::Docker;
+///
+/// # fn main() -> Result<()> {
+/// let docker = Docker::new()?;
+///
+/// for secret in docker.secrets().list::<String>(None)? {
+///     println!("{:?}", secret.spec.and_then(|spec| spec.name));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Secrets<'docker> {
+    docker: &'docker Docker,
+}
+
+impl<'docker> Secrets<'docker> {
+    pub(crate) fn new(docker: &'docker Docker) -> Self {
+        Secrets { docker }
+    }
+
+    /// Create a new secret holding `data`.
+    /// This corresponds to the `POST /secrets/create` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Secret/operation/SecretCreate) for more information.
+    ///
+    /// # Description
+    /// The data is base64 encoded into the `data` field of the spec, replacing its value.
+    /// When the secret is fetched from an external store by a driver, `data` must be empty.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs_models::models::SecretSpec;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let spec = SecretSpec {
+    ///     name: Some("tls_key".to_string()),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let key = std::fs::read("server.key")?;
+    /// let secret = docker.secrets().create(spec, &key)?;
+    /// println!("{}", secret.id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create(&self, mut spec: SecretSpec, data: &[u8]) -> Result<IdResponse> {
+        if !data.is_empty() {
+            spec.data = Some(crate::base64::encode(data));
+        }
+
+        let url = "/secrets/create";
+        let request = RequestBuilder::<(), SecretSpec>::post(url)
+            .body(spec)
+            .build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// List the secrets.
+    /// This corresponds to the `GET /secrets` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Secret/operation/SecretList) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::secret::{ListFilter, ListOption};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let options = ListOption {
+    ///     filters: vec![ListFilter::Label("tls")],
+    /// };
+    ///
+    /// let secrets = docker.secrets().list(Some(options))?;
+    /// println!("{:?}", secrets);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list<T>(&self, options: Option<ListOption<T>>) -> Result<Vec<models::Secret>>
+    where
+        T: AsRef<str> + Serialize,
+    {
+        let url = "/secrets";
+        let request = RequestBuilder::<ListOption<T>, ()>::get(url)
+            .query(options)
+            .build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// Get a secret by id or name.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let secret = docker.secrets().get("insert secret id here");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get<T>(self, id: T) -> Secret<'docker, T>
+    where
+        T: AsRef<str>,
+    {
+        Secret::new(self.docker, id)
+    }
+}
+
+/// Parameters used for the [List Secrets API](Secrets::list)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListOption<T>
+where
+    T: AsRef<str>,
+{
+    /// Filters to process on the secret list.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(serialize_with = "crate::serialize_filters")]
+    pub filters: Vec<ListFilter<T>>,
+}
+
+/// Filters available for the [List Secrets API](Secrets::list).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListFilter<T>
+where
+    T: AsRef<str>,
+{
+    /// Only list the secret with this ID.
+    Id(T),
+    /// Only list the secrets with this label, given as `key` or `key=value`.
+    Label(T),
+    /// Only list the secrets whose name starts with this prefix.
+    Name(T),
+    /// Only list the secret with exactly this name.
+    Names(T),
+}
+
+impl<T> Filter for ListFilter<T>
+where
+    T: AsRef<str>,
+{
+    fn key(&self) -> &'static str {
+        match self {
+            ListFilter::Id(_) => "id",
+            ListFilter::Label(_) => "label",
+            ListFilter::Name(_) => "name",
+            ListFilter::Names(_) => "names",
+        }
+    }
+
+    fn value(&self) -> String {
+        match self {
+            ListFilter::Id(value)
+            | ListFilter::Label(value)
+            | ListFilter::Name(value)
+            | ListFilter::Names(value) => value.as_ref().to_string(),
+        }
+    }
+}
+
+/// Options for the [`update`](Secret::update) method.
+/// This struct corresponds to the param options of the `POST /secrets/(id)/update` endpoint.
+/// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Secret/operation/SecretUpdate) for more information.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct UpdateOption {
+    /// The version number of the secret object being updated.
+    /// This is required to avoid conflicting writes.
+    pub version: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{json_response, MockDaemon};
+
+    #[test]
+    fn create_secret_encodes_data() -> Result<()> {
+        let daemon =
+            MockDaemon::start([json_response(201, r#"{"ID":"ktnbjxoalbkvbvedmg1urrz8h"}"#)]);
+
+        let spec = SecretSpec {
+            name: Some("tls_key".to_string()),
+            ..Default::default()
+        };
+        let secret = daemon.docker().secrets().create(spec, &[0xfb, 0xff])?;
+
+        let request = daemon.request();
+        assert_eq!(request.line, "POST /v1.44/secrets/create HTTP/1.1");
+        assert_eq!(request.body, br#"{"Name":"tls_key","Data":"+/8="}"#);
+        assert_eq!(secret.id, "ktnbjxoalbkvbvedmg1urrz8h");
+
+        Ok(())
+    }
+}
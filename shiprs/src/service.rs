@@ -0,0 +1,675 @@
+use serde::{Serialize, Serializer};
+
+use shiprs_http::uri::PathTemplate;
+use shiprs_http::RequestBuilder;
+use shiprs_models::models::{self, *};
+
+use crate::docker::Docker;
+use crate::error::Result;
+use crate::stream::LogStream;
+use crate::Filter;
+
+/// Interface for interacting with a swarm service.
+///
+/// # Example
+///
+/// ```no_run
+/// # use shiprs::error::Result;
+/// use shiprs::Docker;
+///
+/// # fn main() -> Result<()> {
+/// let docker = Docker::new()?;
+///
+/// let service = docker
+///     .services()
+///     .get("insert service id here")
+///     .inspect(None)?;
+///
+/// println!("{:?}", service);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Service<'docker, T> {
+    docker: &'docker Docker,
+    id: T,
+}
+
+impl<'docker, T> Service<'docker, T>
+where
+    T: AsRef<str>,
+{
+    pub(crate) fn new(docker: &'docker Docker, id: T) -> Self {
+        Service { docker, id }
+    }
+
+    /// Inspect the service.
+    /// This corresponds to the `GET /services/(id)` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Service/operation/ServiceInspect) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::service::InspectOption;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let options = InspectOption {
+    ///     insert_defaults: true,
+    /// };
+    ///
+    /// let service = docker
+    ///     .services()
+    ///     .get("insert service id here")
+    ///     .inspect(Some(options))?;
+    /// println!("{:?}", service.spec);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn inspect(&self, options: Option<InspectOption>) -> Result<models::Service> {
+        let url = PathTemplate::new("/services/{}")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<InspectOption, ()>::get(&url)
+            .query(options)
+            .build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// Update the service.
+    /// This corresponds to the `POST /services/(id)/update` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Service/operation/ServiceUpdate) for more information.
+    ///
+    /// # Description
+    /// The `version` of the service being updated must be given in the options, as returned by [`Service::inspect`].
+    /// The given spec replaces the current one, so all the fields which should be kept must be set.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::service::UpdateOption;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let service = docker.services().get("my_service");
+    ///
+    /// let current = service.inspect(None)?;
+    /// let mut spec = current.spec.unwrap_or_default();
+    /// spec.labels = Some([("team".to_string(), "tools".to_string())].into());
+    ///
+    /// let options = UpdateOption {
+    ///     version: current.version.and_then(|v| v.index).unwrap_or_default(),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let response = service.update(spec, options, None)?;
+    /// println!("{:?}", response.warnings);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn update(
+        &self,
+        spec: ServiceSpec,
+        options: UpdateOption,
+        credentials: Option<AuthConfig>,
+    ) -> Result<ServiceUpdateResponse> {
+        let url = PathTemplate::new("/services/{}/update")
+            .segment(self.id.as_ref())
+            .build()?;
+        let mut request = RequestBuilder::<UpdateOption, ServiceSpec>::post(&url)
+            .query(Some(options))
+            .body(spec);
+        if let Some(credentials) = credentials {
+            request = request.header("X-Registry-Auth", crate::registry_auth(&credentials)?);
+        }
+
+        let request = request.build()?;
+        self.docker.process_into_value(request)
+    }
+
+    /// Remove the service.
+    /// This corresponds to the `DELETE /services/(id)` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Service/operation/ServiceDelete) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// docker.services().get("insert service id here").remove()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remove(&self) -> Result<()> {
+        let url = PathTemplate::new("/services/{}")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<(), ()>::delete(&url).build()?;
+
+        self.docker.process_into_unit(request)
+    }
+
+    /// Get the logs of the tasks of the service.
+    /// This corresponds to the `GET /services/(id)/logs` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Service/operation/ServiceLogs) for more information.
+    ///
+    /// # Description
+    /// The logs are streamed from a dedicated connection, so following them does not block the other requests.
+    /// At least one of `stdout` and `stderr` must be requested.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::service::LogsOption;
+    /// use shiprs::stream::LogOutput;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let options = LogsOption {
+    ///     follow: true,
+    ///     stdout: true,
+    ///     stderr: true,
+    ///     tail: Some(100),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// for output in docker.services().get("my_service").logs(Some(options))? {
+    ///     match output? {
+    ///         LogOutput::StdErr(message) => eprint!("{}", String::from_utf8_lossy(&message)),
+    ///         output => print!("{}", output),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn logs(&self, options: Option<LogsOption>) -> Result<LogStream> {
+        let url = PathTemplate::new("/services/{}/logs")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<LogsOption, ()>::get(&url)
+            .query(options)
+            .build()?;
+
+        let stream = self.docker.process_into_stream(request)?;
+        Ok(LogStream::new(stream, self.docker.limits()))
+    }
+}
+
+/// Interface for interacting with the swarm services.
+///
+/// # Example
+///
+/// ```no_run
+/// # use shiprs::error::Result;
+/// use shiprs::Docker;
+///
+/// # fn main() -> Result<()> {
+/// let docker = Docker::new()?;
+///
+/// for service in docker.services().list::<String>(None)? {
+///     println!("{:?}", service.spec.and_then(|spec| spec.name));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Services<'docker> {
+    docker: &'docker Docker,
+}
+
+impl<'docker> Services<'docker> {
+    pub(crate) fn new(docker: &'docker Docker) -> Self {
+        Services { docker }
+    }
+
+    /// Create a new service.
+    /// This corresponds to the `POST /services/create` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Service/operation/ServiceCreate) for more information.
+    ///
+    /// The credentials are needed when the image of the service is hosted on a private registry.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs_models::models::{ServiceSpec, TaskSpec, TaskSpecContainerSpec};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let spec = ServiceSpec {
+    ///     name: Some("my_service".to_string()),
+    ///     task_template: Some(TaskSpec {
+    ///         container_spec: Some(TaskSpecContainerSpec {
+    ///             image: Some("nginx:alpine".to_string()),
+    ///             ..Default::default()
+    ///         }),
+    ///         ..Default::default()
+    ///     }),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let service = docker.services().create(spec, None)?;
+    /// println!("{:?}", service.id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create(
+        &self,
+        spec: ServiceSpec,
+        credentials: Option<AuthConfig>,
+    ) -> Result<ServiceCreateResponse> {
+        let url = "/services/create";
+        let mut request = RequestBuilder::<(), ServiceSpec>::post(url).body(spec);
+        if let Some(credentials) = credentials {
+            request = request.header("X-Registry-Auth", crate::registry_auth(&credentials)?);
+        }
+
+        let request = request.build()?;
+        self.docker.process_into_value(request)
+    }
+
+    /// List the services.
+    /// This corresponds to the `GET /services` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Service/operation/ServiceList) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::service::{ListFilter, ListOption, ServiceMode};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let options = ListOption {
+    ///     filters: vec![ListFilter::Mode(ServiceMode::Replicated), ListFilter::Label("team=tools")],
+    ///     status: true,
+    /// };
+    ///
+    /// let services = docker.services().list(Some(options))?;
+    /// println!("{:?}", services);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list<T>(&self, options: Option<ListOption<T>>) -> Result<Vec<models::Service>>
+    where
+        T: AsRef<str> + Serialize,
+    {
+        let url = "/services";
+        let request = RequestBuilder::<ListOption<T>, ()>::get(url)
+            .query(options)
+            .build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// Get a service by id or name.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let service = docker.services().get("insert service id here");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get<T>(self, id: T) -> Service<'docker, T>
+    where
+        T: AsRef<str>,
+    {
+        Service::new(self.docker, id)
+    }
+}
+
+/// Parameters used for the [List Services API](Services::list)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListOption<T>
+where
+    T: AsRef<str>,
+{
+    /// Filters to process on the service list.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(serialize_with = "crate::serialize_filters")]
+    pub filters: Vec<ListFilter<T>>,
+
+    /// Include the number of running and desired tasks of each service.
+    pub status: bool,
+}
+
+/// Filters available for the [List Services API](Services::list).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListFilter<T>
+where
+    T: AsRef<str>,
+{
+    /// Only list the service with this ID.
+    Id(T),
+    /// Only list the services with this label, given as `key` or `key=value`.
+    Label(T),
+    /// Only list the services running in this mode.
+    Mode(ServiceMode),
+    /// Only list the services with this name.
+    Name(T),
+}
+
+/// The scheduling modes a service can be filtered on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceMode {
+    Replicated,
+    Global,
+}
+
+impl<T> Filter for ListFilter<T>
+where
+    T: AsRef<str>,
+{
+    fn key(&self) -> &'static str {
+        match self {
+            ListFilter::Id(_) => "id",
+            ListFilter::Label(_) => "label",
+            ListFilter::Mode(_) => "mode",
+            ListFilter::Name(_) => "name",
+        }
+    }
+
+    fn value(&self) -> String {
+        match self {
+            ListFilter::Id(value) | ListFilter::Label(value) | ListFilter::Name(value) => {
+                value.as_ref().to_string()
+            }
+            ListFilter::Mode(ServiceMode::Replicated) => "replicated".to_string(),
+            ListFilter::Mode(ServiceMode::Global) => "global".to_string(),
+        }
+    }
+}
+
+/// Options for the [`inspect`](Service::inspect) method.
+/// This struct corresponds to the param options of the `GET /services/(id)` endpoint.
+/// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Service/operation/ServiceInspect) for more information.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InspectOption {
+    /// Fill in the default values of the fields which are not set.
+    pub insert_defaults: bool,
+}
+
+impl From<bool> for InspectOption {
+    fn from(insert_defaults: bool) -> Self {
+        InspectOption { insert_defaults }
+    }
+}
+
+/// Options for the [`update`](Service::update) method.
+/// This struct corresponds to the param options of the `POST /services/(id)/update` endpoint.
+/// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Service/operation/ServiceUpdate) for more information.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateOption {
+    /// The version number of the service object being updated.
+    /// This is required to avoid conflicting writes.
+    pub version: u64,
+
+    /// Which spec to take the registry credentials from, when no credentials are given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registry_auth_from: Option<RegistryAuthFrom>,
+
+    /// Roll the service back to its previous spec, as set before the last update.
+    /// The given spec is ignored when set.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    #[serde(serialize_with = "serialize_rollback")]
+    pub rollback: bool,
+}
+
+/// The spec from which the registry credentials of a service update are taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RegistryAuthFrom {
+    /// The spec given with the update.
+    Spec,
+    /// The spec of the service before the update.
+    PreviousSpec,
+}
+
+fn serialize_rollback<S: Serializer>(_: &bool, s: S) -> std::result::Result<S::Ok, S::Error> {
+    s.serialize_str("previous")
+}
+
+/// Options for the [`logs`](Service::logs) method.
+/// This struct corresponds to the param options of the `GET /services/(id)/logs` endpoint.
+/// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Service/operation/ServiceLogs) for more information.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct LogsOption {
+    /// Show service context and extra details provided to logs.
+    pub details: bool,
+
+    /// Keep the connection open and stream the new logs as they are written.
+    pub follow: bool,
+
+    /// Return the logs written to the standard output.
+    pub stdout: bool,
+
+    /// Return the logs written to the standard error.
+    pub stderr: bool,
+
+    /// Only return the logs written since this UNIX timestamp.
+    pub since: i64,
+
+    /// Prefix every log line with its timestamp.
+    pub timestamps: bool,
+
+    /// Only return this number of lines from the end of the logs. All the lines are returned by default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tail: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{json_response, MockDaemon};
+    use crate::stream::LogOutput;
+
+    #[test]
+    fn create_service_with_credentials() -> Result<()> {
+        let daemon =
+            MockDaemon::start([json_response(201, r#"{"ID":"ak7w3gjqoa3kuz8xcpnyy0pvl"}"#)]);
+
+        let spec = ServiceSpec {
+            name: Some("web".to_string()),
+            ..Default::default()
+        };
+        let credentials = AuthConfig {
+            username: Some("shiprs".to_string()),
+            ..Default::default()
+        };
+        let response = daemon.docker().services().create(spec, Some(credentials))?;
+
+        let request = daemon.request();
+        assert_eq!(request.line, "POST /v1.44/services/create HTTP/1.1");
+        assert_eq!(
+            request.header("X-Registry-Auth"),
+            Some("eyJ1c2VybmFtZSI6InNoaXBycyJ9")
+        );
+        assert_eq!(request.body, br#"{"Name":"web"}"#);
+        assert_eq!(response.id, Some("ak7w3gjqoa3kuz8xcpnyy0pvl".to_string()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn list_services_with_filters() -> Result<()> {
+        let daemon = MockDaemon::start([json_response(200, "[]")]);
+
+        let options = ListOption {
+            filters: vec![
+                ListFilter::Mode(ServiceMode::Global),
+                ListFilter::Name("web"),
+            ],
+            status: true,
+        };
+        daemon.docker().services().list(Some(options))?;
+
+        assert_eq!(
+            daemon.request().line,
+            "GET /v1.44/services?filters=%7B%22mode%22%3A%5B%22global%22%5D%2C%22name%22%3A%5B%22web%22%5D%7D&status=true HTTP/1.1"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn inspect_service_with_defaults() -> Result<()> {
+        let daemon = MockDaemon::start([
+            json_response(200, r#"{"ID":"ak7w3gjqoa3kuz8xcpnyy0pvl"}"#),
+            json_response(200, r#"{"ID":"ak7w3gjqoa3kuz8xcpnyy0pvl"}"#),
+        ]);
+        let docker = daemon.docker();
+
+        let service = docker.services().get("web").inspect(Some(true.into()))?;
+        assert_eq!(service.id.as_deref(), Some("ak7w3gjqoa3kuz8xcpnyy0pvl"));
+        assert_eq!(
+            daemon.request().line,
+            "GET /v1.44/services/web?insertDefaults=true HTTP/1.1"
+        );
+
+        docker.services().get("web").inspect(None)?;
+        assert_eq!(daemon.request().line, "GET /v1.44/services/web HTTP/1.1");
+
+        Ok(())
+    }
+
+    #[test]
+    fn update_service_with_rollback() -> Result<()> {
+        let daemon = MockDaemon::start([json_response(200, r#"{"Warnings":[]}"#)]);
+
+        let options = UpdateOption {
+            version: 12,
+            registry_auth_from: Some(RegistryAuthFrom::PreviousSpec),
+            rollback: true,
+        };
+        daemon
+            .docker()
+            .services()
+            .get("web")
+            .update(ServiceSpec::default(), options, None)?;
+
+        let request = daemon.request();
+        assert_eq!(
+            request.line,
+            "POST /v1.44/services/web/update?version=12&registryAuthFrom=previous-spec&rollback=previous HTTP/1.1"
+        );
+        assert_eq!(request.header("X-Registry-Auth"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn demultiplex_service_logs() -> Result<()> {
+        let mut response =
+            b"HTTP/1.1 200 OK\r\nContent-Type: application/vnd.docker.multiplexed-stream\r\nContent-Length: 29\r\n\r\n"
+                .to_vec();
+        response.extend_from_slice(&[1, 0, 0, 0, 0, 0, 0, 6]);
+        response.extend_from_slice(b"hello\n");
+        response.extend_from_slice(&[2, 0, 0, 0, 0, 0, 0, 7]);
+        response.extend_from_slice(b"failed\n");
+        let daemon = MockDaemon::start([response]);
+
+        let options = LogsOption {
+            stdout: true,
+            stderr: true,
+            tail: Some(10),
+            ..Default::default()
+        };
+        let logs = daemon
+            .docker()
+            .services()
+            .get("web")
+            .logs(Some(options))?
+            .collect::<Result<Vec<_>>>()?;
+
+        assert_eq!(
+            daemon.request().line,
+            "GET /v1.44/services/web/logs?details=false&follow=false&stdout=true&stderr=true&since=0&timestamps=false&tail=10 HTTP/1.1"
+        );
+        assert_eq!(
+            logs,
+            vec![
+                LogOutput::StdOut(b"hello\n".to_vec()),
+                LogOutput::StdErr(b"failed\n".to_vec()),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_tty_service_logs() -> Result<()> {
+        // Looks like the header of a frame written to the standard output.
+        let mut response =
+            b"HTTP/1.1 200 OK\r\nContent-Type: application/vnd.docker.raw-stream\r\nContent-Length: 16\r\n\r\n"
+                .to_vec();
+        response.extend_from_slice(b"\x01\0\0\0hello world\n");
+        let daemon = MockDaemon::start([response]);
+
+        let logs = daemon
+            .docker()
+            .services()
+            .get("web")
+            .logs(None)?
+            .collect::<Result<Vec<_>>>()?;
+
+        assert_eq!(
+            logs,
+            vec![LogOutput::Console(b"\x01\0\0\0hello world\n".to_vec())]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn refuse_large_log_frames() -> Result<()> {
+        let mut response =
+            b"HTTP/1.1 200 OK\r\nContent-Type: application/vnd.docker.multiplexed-stream\r\nContent-Length: 8\r\n\r\n"
+                .to_vec();
+        response.extend_from_slice(&[1, 0, 0, 0, 0xff, 0xff, 0xff, 0xff]);
+        let daemon = MockDaemon::start([response]);
+
+        let mut logs = daemon.docker().services().get("web").logs(None)?;
+
+        let err = logs.next().unwrap().unwrap_err();
+        assert!(err.to_string().contains("larger than the limit"), "{}", err);
+
+        Ok(())
+    }
+
+    #[test]
+    fn stop_logs_after_an_error() -> Result<()> {
+        let mut response =
+            b"HTTP/1.1 200 OK\r\nContent-Type: application/vnd.docker.multiplexed-stream\r\nContent-Length: 21\r\n\r\n"
+                .to_vec();
+        response.extend_from_slice(&[7, 0, 0, 0, 0, 0, 0, 3]);
+        response.extend_from_slice(b"abc");
+        response.extend_from_slice(&[1, 0, 0, 0, 0, 0, 0, 2]);
+        response.extend_from_slice(b"hi");
+        let daemon = MockDaemon::start([response]);
+
+        let mut logs = daemon.docker().services().get("web").logs(None)?;
+
+        let err = logs.next().unwrap().unwrap_err();
+        assert!(
+            err.to_string().contains("unknown log stream type"),
+            "{}",
+            err
+        );
+        assert!(logs.next().is_none());
+        assert!(logs.next().is_none());
+
+        Ok(())
+    }
+}
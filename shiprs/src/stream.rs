@@ -0,0 +1,240 @@
+use std::io::{self, Read};
+
+use shiprs_http::{HeaderMap, Limits};
+use shiprs_models::models::CreateImageInfo;
+
+use crate::error::{Error, Result};
+use crate::transport::Body;
+
+/// A response body streamed from the docker daemon.
+///
+/// The body is read straight from the connection as it is consumed, which allows large payloads
+/// such as image tarballs to be processed without holding them in memory.
+///
+/// # Example
+///
+/// ```no_run
+/// # use shiprs::error::Result;
+/// use std::fs::File;
+/// use shiprs::Docker;
+///
+/// # fn main() -> Result<()> {
+/// let docker = Docker::new()?;
+///
+/// let mut tarball = docker.images().export(&["hello-world"])?;
+/// std::io::copy(&mut tarball, &mut File::create("hello-world.tar")?)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct BodyStream {
+    inner: Body,
+    headers: HeaderMap,
+}
+
+impl BodyStream {
+    pub(crate) fn new(inner: Body, headers: HeaderMap) -> Self {
+        BodyStream { inner, headers }
+    }
+
+    /// The headers of the response.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+}
+
+impl Read for BodyStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl std::fmt::Debug for BodyStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("stream::BodyStream")
+            .field("headers", &self.headers)
+            .field("done", &self.inner.is_done())
+            .finish()
+    }
+}
+
+/// A chunk of output of a container or a service, as streamed by the logs endpoints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogOutput {
+    /// Output written to the standard input, when it is attached.
+    StdIn(Vec<u8>),
+    /// Output written to the standard output.
+    StdOut(Vec<u8>),
+    /// Output written to the standard error.
+    StdErr(Vec<u8>),
+    /// Output of a TTY, where the standard output and error are not told apart.
+    Console(Vec<u8>),
+}
+
+impl LogOutput {
+    /// The raw bytes of the output.
+    pub fn message(&self) -> &[u8] {
+        match self {
+            LogOutput::StdIn(message)
+            | LogOutput::StdOut(message)
+            | LogOutput::StdErr(message)
+            | LogOutput::Console(message) => message,
+        }
+    }
+}
+
+impl std::fmt::Display for LogOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&String::from_utf8_lossy(self.message()))
+    }
+}
+
+/// Decoder of the multiplexed stream format used by the docker daemon for logs.
+///
+/// Each frame is prefixed by an 8 bytes header holding the stream it was written to, followed by
+/// the big endian length of the frame. When a TTY is allocated, the output is sent as is instead,
+/// with the `application/vnd.docker.raw-stream` content type, and is yielded as
+/// [`LogOutput::Console`] chunks.
+///
+/// Frames larger than [`Limits::max_chunk_size`] are refused. The stream ends after the first
+/// error, as the start of the next frame is then unknown.
+///
+/// # Example
+///
+/// ```no_run
+/// # use shiprs::error::Result;
+/// use shiprs::Docker;
+/// use shiprs::service::LogsOption;
+///
+/// # fn main() -> Result<()> {
+/// let docker = Docker::new()?;
+/// let options = LogsOption {
+///     stdout: true,
+///     stderr: true,
+///     ..Default::default()
+/// };
+///
+/// for output in docker.services().get("my_service").logs(Some(options))? {
+///     print!("{}", output?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct LogStream {
+    inner: BodyStream,
+    multiplexed: bool,
+    max_frame_size: usize,
+    failed: bool,
+}
+
+impl LogStream {
+    pub(crate) fn new(inner: BodyStream, limits: &Limits) -> Self {
+        let media_type = inner
+            .headers()
+            .content_type()
+            .and_then(|t| t.split(';').next());
+        let multiplexed = !media_type.is_some_and(|t| t.trim().eq_ignore_ascii_case(RAW_STREAM));
+        LogStream {
+            inner,
+            multiplexed,
+            max_frame_size: limits.max_chunk_size,
+            failed: false,
+        }
+    }
+
+    fn read_frame(&mut self) -> Result<Option<LogOutput>> {
+        if !self.multiplexed {
+            return self.read_raw();
+        }
+
+        let mut header = [0u8; 8];
+        match read_full(&mut self.inner, &mut header)? {
+            0 => return Ok(None),
+            8 => {}
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "log stream ended in the middle of a frame header",
+                )
+                .into())
+            }
+        }
+
+        let len = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        if len > self.max_frame_size {
+            return Err(Error::docker_stream(format!(
+                "log frame of {} bytes is larger than the limit of {} bytes",
+                len, self.max_frame_size
+            )));
+        }
+        let mut message = vec![0; len];
+        self.inner.read_exact(&mut message)?;
+
+        match header[0] {
+            0 => Ok(Some(LogOutput::StdIn(message))),
+            1 => Ok(Some(LogOutput::StdOut(message))),
+            2 => Ok(Some(LogOutput::StdErr(message))),
+            typ => Err(Error::docker_stream(format!(
+                "unknown log stream type: {}",
+                typ
+            ))),
+        }
+    }
+
+    fn read_raw(&mut self) -> Result<Option<LogOutput>> {
+        let mut message = vec![0; RAW_CHUNK_SIZE];
+        let n = self.inner.read(&mut message)?;
+        message.truncate(n);
+
+        if message.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(LogOutput::Console(message)))
+        }
+    }
+}
+
+/// The content type of the logs of a TTY, which are not multiplexed.
+const RAW_STREAM: &str = "application/vnd.docker.raw-stream";
+
+const RAW_CHUNK_SIZE: usize = 8192;
+
+impl Iterator for LogStream {
+    type Item = Result<LogOutput>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let frame = self.read_frame();
+        self.failed = frame.is_err();
+        frame.transpose()
+    }
+}
+
+/// Read until `buf` is full or the end of the stream is reached, returning the number of bytes read.
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Read a JSON progress stream, e.g. of a pull or a push, to its end, failing on the first
+/// error reported by the daemon.
+pub(crate) fn wait_progress<R: Read>(reader: R) -> Result<()> {
+    for message in serde_json::Deserializer::from_reader(reader).into_iter::<CreateImageInfo>() {
+        if let Some(error) = message?.error {
+            return Err(Error::docker_stream(error));
+        }
+    }
+
+    Ok(())
+}
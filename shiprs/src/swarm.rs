@@ -0,0 +1,321 @@
+use serde::Serialize;
+
+use shiprs_http::RequestBuilder;
+use shiprs_models::models::{self, *};
+
+use crate::docker::Docker;
+use crate::error::Result;
+
+/// Interface for interacting with the swarm the docker engine is part of.
+///
+/// # Example
+/// ```no_run
+/// # use shiprs::error::Result;
+/// use shiprs::Docker;
+///
+/// # fn main() -> Result<()> {
+/// let docker = Docker::new()?;
+///
+/// let swarm = docker.swarm().inspect()?;
+/// println!("Swarm ID: {:?}", swarm.id);
+///
+/// # Ok(())
+/// # }
+/// ```
+pub struct Swarm<'docker> {
+    docker: &'docker Docker,
+}
+
+impl<'docker> Swarm<'docker> {
+    pub(crate) fn new(docker: &'docker Docker) -> Self {
+        Swarm { docker }
+    }
+
+    /// Initialize a new swarm.
+    /// This corresponds to the `POST /swarm/init` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Swarm/operation/SwarmInit) for more information.
+    ///
+    /// Returns the ID of the node, which is the first manager of the new swarm.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs_models::models::SwarmInitRequest;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let request = SwarmInitRequest {
+    ///     listen_addr: Some("0.0.0.0:2377".to_string()),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let node_id = docker.swarm().init(request)?;
+    /// println!("{}", node_id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn init(&self, request: SwarmInitRequest) -> Result<String> {
+        let url = "/swarm/init";
+        let request = RequestBuilder::<(), SwarmInitRequest>::post(url)
+            .body(request)
+            .build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// Join an existing swarm.
+    /// This corresponds to the `POST /swarm/join` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Swarm/operation/SwarmJoin) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs_models::models::SwarmJoinRequest;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let request = SwarmJoinRequest {
+    ///     listen_addr: Some("0.0.0.0:2377".to_string()),
+    ///     remote_addrs: Some(vec!["node1:2377".to_string()]),
+    ///     join_token: Some("insert join token here".to_string()),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// docker.swarm().join(request)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn join(&self, request: SwarmJoinRequest) -> Result<()> {
+        let url = "/swarm/join";
+        let request = RequestBuilder::<(), SwarmJoinRequest>::post(url)
+            .body(request)
+            .build()?;
+
+        self.docker.process_into_unit(request)
+    }
+
+    /// Leave the swarm.
+    /// This corresponds to the `POST /swarm/leave` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Swarm/operation/SwarmLeave) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::swarm::LeaveOption;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    ///
+    /// docker.swarm().leave(Some(LeaveOption { force: true }))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn leave(&self, options: Option<LeaveOption>) -> Result<()> {
+        let url = "/swarm/leave";
+        let request = RequestBuilder::<LeaveOption, ()>::post(url)
+            .query(options)
+            .build()?;
+
+        self.docker.process_into_unit(request)
+    }
+
+    /// Inspect the swarm.
+    /// This corresponds to the `GET /swarm` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Swarm/operation/SwarmInspect) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    ///
+    /// let swarm = docker.swarm().inspect()?;
+    /// println!("{:?}", swarm.join_tokens);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn inspect(&self) -> Result<models::Swarm> {
+        let url = "/swarm";
+        let request = RequestBuilder::<(), ()>::get(url).build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// Update the swarm.
+    /// This corresponds to the `POST /swarm/update` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Swarm/operation/SwarmUpdate) for more information.
+    ///
+    /// # Description
+    /// The `version` of the swarm being updated must be given in the options, as returned by [`Swarm::inspect`].
+    /// The update is refused by the daemon if the swarm has been updated in the meantime.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::swarm::UpdateOption;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    ///
+    /// let swarm = docker.swarm().inspect()?;
+    /// let options = UpdateOption {
+    ///     version: swarm.version.and_then(|v| v.index).unwrap_or_default(),
+    ///     rotate_worker_token: true,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// docker.swarm().update(swarm.spec.unwrap_or_default(), options)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn update(&self, spec: SwarmSpec, options: UpdateOption) -> Result<()> {
+        let url = "/swarm/update";
+        let request = RequestBuilder::<UpdateOption, SwarmSpec>::post(url)
+            .query(Some(options))
+            .body(spec)
+            .build()?;
+
+        self.docker.process_into_unit(request)
+    }
+
+    /// Get the unlock key of the swarm.
+    /// This corresponds to the `GET /swarm/unlockkey` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Swarm/operation/SwarmUnlockkey) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    ///
+    /// let key = docker.swarm().unlock_key()?;
+    /// println!("{:?}", key.unlock_key);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn unlock_key(&self) -> Result<UnlockKeyResponse> {
+        let url = "/swarm/unlockkey";
+        let request = RequestBuilder::<(), ()>::get(url).build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// Unlock a locked manager.
+    /// This corresponds to the `POST /swarm/unlock` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Swarm/operation/SwarmUnlock) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs_models::models::SwarmUnlockRequest;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let request = SwarmUnlockRequest {
+    ///     unlock_key: Some("insert unlock key here".to_string()),
+    /// };
+    ///
+    /// docker.swarm().unlock(request)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn unlock(&self, request: SwarmUnlockRequest) -> Result<()> {
+        let url = "/swarm/unlock";
+        let request = RequestBuilder::<(), SwarmUnlockRequest>::post(url)
+            .body(request)
+            .build()?;
+
+        self.docker.process_into_unit(request)
+    }
+}
+
+/// Options for the [`leave`](Swarm::leave) method.
+/// This struct corresponds to the param options of the `POST /swarm/leave` endpoint.
+/// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Swarm/operation/SwarmLeave) for more information.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct LeaveOption {
+    /// Force leave swarm, even if this is the last manager or that it will break the cluster.
+    pub force: bool,
+}
+
+/// Options for the [`update`](Swarm::update) method.
+/// This struct corresponds to the param options of the `POST /swarm/update` endpoint.
+/// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Swarm/operation/SwarmUpdate) for more information.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateOption {
+    /// The version number of the swarm object being updated.
+    /// This is required to avoid conflicting writes.
+    pub version: u64,
+
+    /// Rotate the worker join token.
+    pub rotate_worker_token: bool,
+
+    /// Rotate the manager join token.
+    pub rotate_manager_token: bool,
+
+    /// Rotate the manager unlock key.
+    pub rotate_manager_unlock_key: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{json_response, MockDaemon};
+
+    #[test]
+    fn init_swarm() -> Result<()> {
+        let daemon = MockDaemon::start([json_response(200, r#""7v2t30z9blmxuhnyo6s4cpenp""#)]);
+
+        let request = SwarmInitRequest {
+            listen_addr: Some("0.0.0.0:2377".to_string()),
+            force_new_cluster: Some(false),
+            ..Default::default()
+        };
+        let node_id = daemon.docker().swarm().init(request)?;
+
+        let request = daemon.request();
+        assert_eq!(request.line, "POST /v1.44/swarm/init HTTP/1.1");
+        assert_eq!(
+            request.body,
+            br#"{"ListenAddr":"0.0.0.0:2377","ForceNewCluster":false}"#
+        );
+        assert_eq!(node_id, "7v2t30z9blmxuhnyo6s4cpenp");
+
+        Ok(())
+    }
+
+    #[test]
+    fn update_swarm() -> Result<()> {
+        let daemon = MockDaemon::start(["HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n"]);
+
+        let spec = SwarmSpec {
+            name: Some("default".to_string()),
+            ..Default::default()
+        };
+        let options = UpdateOption {
+            version: 42,
+            rotate_worker_token: true,
+            ..Default::default()
+        };
+        daemon.docker().swarm().update(spec, options)?;
+
+        let request = daemon.request();
+        assert_eq!(
+            request.line,
+            "POST /v1.44/swarm/update?version=42&rotateWorkerToken=true&rotateManagerToken=false&rotateManagerUnlockKey=false HTTP/1.1"
+        );
+        assert_eq!(request.body, br#"{"Name":"default"}"#);
+
+        Ok(())
+    }
+}
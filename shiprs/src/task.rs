@@ -0,0 +1,283 @@
+use serde::Serialize;
+
+use shiprs_http::uri::PathTemplate;
+use shiprs_http::RequestBuilder;
+use shiprs_models::models::{self, TaskState};
+
+use crate::docker::Docker;
+use crate::error::Result;
+use crate::service::LogsOption;
+use crate::stream::LogStream;
+use crate::Filter;
+
+/// Interface for interacting with a swarm task.
+///
+/// # Example
+///
+/// ```no_run
+/// # use shiprs::error::Result;
+/// use shiprs::Docker;
+///
+/// # fn main() -> Result<()> {
+/// let docker = Docker::new()?;
+///
+/// let task = docker.tasks().get("insert task id here").inspect()?;
+///
+/// println!("{:?} runs on {:?}", task.id, task.node_id);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Task<'docker, T> {
+    docker: &'docker Docker,
+    id: T,
+}
+
+impl<'docker, T> Task<'docker, T>
+where
+    T: AsRef<str>,
+{
+    pub(crate) fn new(docker: &'docker Docker, id: T) -> Self {
+        Task { docker, id }
+    }
+
+    /// Inspect the task.
+    /// This corresponds to the `GET /tasks/(id)` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Task/operation/TaskInspect) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let task = docker.tasks().get("insert task id here").inspect()?;
+    /// println!("{:?}", task.status);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn inspect(&self) -> Result<models::Task> {
+        let url = PathTemplate::new("/tasks/{}")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<(), ()>::get(&url).build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// Get the logs of the task.
+    /// This corresponds to the `GET /tasks/(id)/logs` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Task/operation/TaskLogs) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::service::LogsOption;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let options = LogsOption {
+    ///     stdout: true,
+    ///     stderr: true,
+    ///     timestamps: true,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// for output in docker.tasks().get("insert task id here").logs(Some(options))? {
+    ///     print!("{}", output?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn logs(&self, options: Option<LogsOption>) -> Result<LogStream> {
+        let url = PathTemplate::new("/tasks/{}/logs")
+            .segment(self.id.as_ref())
+            .build()?;
+        let request = RequestBuilder::<LogsOption, ()>::get(&url)
+            .query(options)
+            .build()?;
+
+        let stream = self.docker.process_into_stream(request)?;
+        Ok(LogStream::new(stream, self.docker.limits()))
+    }
+}
+
+/// Interface for interacting with the swarm tasks.
+///
+/// # Example
+///
+/// ```no_run
+/// # use shiprs::error::Result;
+/// use shiprs::Docker;
+///
+/// # fn main() -> Result<()> {
+/// let docker = Docker::new()?;
+///
+/// for task in docker.tasks().list::<String>(None)? {
+///     println!("{:?} runs on {:?}", task.id, task.node_id);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Tasks<'docker> {
+    docker: &'docker Docker,
+}
+
+impl<'docker> Tasks<'docker> {
+    pub(crate) fn new(docker: &'docker Docker) -> Self {
+        Tasks { docker }
+    }
+
+    /// List the tasks.
+    /// This corresponds to the `GET /tasks` endpoint.
+    /// See the [API documentation](https://docs.docker.com/engine/api/v1.44/#tag/Task/operation/TaskList) for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    /// use shiprs::task::{ListFilter, ListOption};
+    /// use shiprs_models::models::TaskState;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let options = ListOption {
+    ///     filters: vec![
+    ///         ListFilter::Service("my_service"),
+    ///         ListFilter::DesiredState(TaskState::RUNNING),
+    ///     ],
+    /// };
+    ///
+    /// let tasks = docker.tasks().list(Some(options))?;
+    /// println!("{:?}", tasks);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list<T>(&self, options: Option<ListOption<T>>) -> Result<Vec<models::Task>>
+    where
+        T: AsRef<str> + Serialize,
+    {
+        let url = "/tasks";
+        let request = RequestBuilder::<ListOption<T>, ()>::get(url)
+            .query(options)
+            .build()?;
+
+        self.docker.process_into_value(request)
+    }
+
+    /// Get a task by id.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use shiprs::error::Result;
+    /// use shiprs::Docker;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let docker = Docker::new()?;
+    /// let task = docker.tasks().get("insert task id here");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get<T>(self, id: T) -> Task<'docker, T>
+    where
+        T: AsRef<str>,
+    {
+        Task::new(self.docker, id)
+    }
+}
+
+/// Parameters used for the [List Tasks API](Tasks::list)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListOption<T>
+where
+    T: AsRef<str>,
+{
+    /// Filters to process on the task list.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(serialize_with = "crate::serialize_filters")]
+    pub filters: Vec<ListFilter<T>>,
+}
+
+/// Filters available for the [List Tasks API](Tasks::list).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListFilter<T>
+where
+    T: AsRef<str>,
+{
+    /// Only list the tasks in this desired state, one of `running`, `shutdown` or `accepted`.
+    DesiredState(TaskState),
+    /// Only list the task with this ID.
+    Id(T),
+    /// Only list the tasks with this label, given as `key` or `key=value`.
+    Label(T),
+    /// Only list the tasks with this name.
+    Name(T),
+    /// Only list the tasks scheduled on this node, given by ID or name.
+    Node(T),
+    /// Only list the tasks of this service, given by ID or name.
+    Service(T),
+}
+
+impl<T> Filter for ListFilter<T>
+where
+    T: AsRef<str>,
+{
+    fn key(&self) -> &'static str {
+        match self {
+            ListFilter::DesiredState(_) => "desired-state",
+            ListFilter::Id(_) => "id",
+            ListFilter::Label(_) => "label",
+            ListFilter::Name(_) => "name",
+            ListFilter::Node(_) => "node",
+            ListFilter::Service(_) => "service",
+        }
+    }
+
+    fn value(&self) -> String {
+        match self {
+            ListFilter::DesiredState(state) => state.to_string(),
+            ListFilter::Id(value)
+            | ListFilter::Label(value)
+            | ListFilter::Name(value)
+            | ListFilter::Node(value)
+            | ListFilter::Service(value) => value.as_ref().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{json_response, MockDaemon};
+
+    #[test]
+    fn list_tasks_with_filters() -> Result<()> {
+        let daemon = MockDaemon::start([json_response(
+            200,
+            r#"[{"ID":"0kzzo1i0y4jz6027t0k7aezc7","NodeID":"60gvrl6tm78dmak4yl7srz94v","DesiredState":"running"}]"#,
+        )]);
+
+        let options = ListOption {
+            filters: vec![
+                ListFilter::Service("web"),
+                ListFilter::DesiredState(TaskState::RUNNING),
+            ],
+        };
+        let tasks = daemon.docker().tasks().list(Some(options))?;
+
+        assert_eq!(
+            daemon.request().line,
+            "GET /v1.44/tasks?filters=%7B%22desired-state%22%3A%5B%22running%22%5D%2C%22service%22%3A%5B%22web%22%5D%7D HTTP/1.1"
+        );
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(
+            tasks[0].node_id,
+            Some("60gvrl6tm78dmak4yl7srz94v".to_string())
+        );
+        assert_eq!(tasks[0].desired_state, Some(TaskState::RUNNING));
+
+        Ok(())
+    }
+}
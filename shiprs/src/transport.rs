@@ -1,57 +1,252 @@
-use std::io::{BufReader, Write};
+use std::io::{self, BufWriter};
+use std::mem::MaybeUninit;
+use std::os::fd::OwnedFd;
 use std::os::unix::net::UnixStream;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use serde::Serialize;
-use shiprs_http::{Request, Response};
+use socket2::{Domain, SockAddr, SockRef, Socket, Type};
 
-use crate::error::Result;
+use shiprs_http::{BodyReader, Limits, Method, ReadBuffer, Request, Response};
+
+use crate::docker::Timeouts;
+use crate::error::{Error, Result};
+
+/// The unread body of a response received through [`Transport::stream`].
+pub(crate) type Body = BodyReader<ReadBuffer<UnixStream>>;
 
 pub(crate) enum Transport {
     Unix {
-        client: Client<UnixStream>,
+        /// The idle connections kept open for the next buffered requests.
+        idle: Mutex<Vec<UnixStream>>,
         path: String,
+        limits: Limits,
     },
 }
 
+/// The maximum number of idle connections kept open.
+const MAX_IDLE_CONNECTIONS: usize = 4;
+
 impl Transport {
-    pub(crate) fn unix<S: Into<String>>(socket: S) -> Result<Self> {
+    pub(crate) fn unix<S: Into<String>>(
+        socket: S,
+        timeouts: &Timeouts,
+        limits: Limits,
+    ) -> Result<Self> {
         let socket = socket.into();
         Ok(Transport::Unix {
-            client: Client {
-                socket: UnixStream::connect(&socket)?,
-            },
+            idle: Mutex::new(vec![connect(&socket, timeouts.connect)?]),
             path: socket,
+            limits,
         })
     }
 
-    pub(crate) fn request<S>(&self, req: Request<S>) -> Result<Response>
-    where
-        S: Serialize,
-    {
+    /// The limits applied when parsing the responses of the daemon.
+    pub(crate) fn limits(&self) -> &Limits {
+        match self {
+            Transport::Unix { limits, .. } => limits,
+        }
+    }
+
+    /// Send a request on an idle connection, or a new one if there is none, and read the whole
+    /// response.
+    ///
+    /// The connection is only used by one request at a time, and is kept for the next requests
+    /// once the response has been read. It is dropped when the exchange fails, e.g. on a timeout,
+    /// as the rest of the response may still be on its way.
+    ///
+    /// An idle connection closed by the daemon in the meantime, e.g. because it restarted, is
+    /// replaced by a new one before sending the request.
+    pub(crate) fn request(&self, req: Request, timeouts: &Timeouts) -> Result<Response> {
+        match self {
+            Transport::Unix { idle, path, limits } => {
+                let reused = lock(idle).pop();
+                let connection = match reused {
+                    Some(connection) if is_open(&connection) => connection,
+                    _ => connect(path, timeouts.connect).map_err(Error::unanswered)?,
+                };
+
+                let res = send(&connection, req, timeouts, limits)?;
+                if !closes_connection(&res) {
+                    let mut idle = lock(idle);
+                    if idle.len() < MAX_IDLE_CONNECTIONS {
+                        idle.push(connection);
+                    }
+                }
+
+                Ok(res)
+            }
+        }
+    }
+
+    /// Send a request on a dedicated connection and return the response with its body left
+    /// unread, so that large or long-lived responses don't hold an idle connection.
+    ///
+    /// The read timeout only applies until the response headers are received, the body is then
+    /// read with the [`stream_idle`](Timeouts::stream_idle) timeout.
+    pub(crate) fn stream(&self, req: Request, timeouts: &Timeouts) -> Result<(Response, Body)> {
         match self {
-            Transport::Unix { client, .. } => client.request(req),
+            Transport::Unix { path, limits, .. } => {
+                let connection = connect(path, timeouts.connect).map_err(Error::unanswered)?;
+                send_streaming(connection, req, timeouts, limits)
+            }
+        }
+    }
+}
+
+fn lock(idle: &Mutex<Vec<UnixStream>>) -> MutexGuard<'_, Vec<UnixStream>> {
+    idle.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns `true` if the daemon has not closed the idle `socket`, which then has nothing to read.
+fn is_open(socket: &UnixStream) -> bool {
+    let mut buf = [MaybeUninit::uninit()];
+    if socket.set_nonblocking(true).is_err() {
+        return false;
+    }
+    let open = matches!(
+        SockRef::from(socket).peek(&mut buf),
+        Err(err) if err.kind() == io::ErrorKind::WouldBlock
+    );
+    socket.set_nonblocking(false).is_ok() && open
+}
+
+/// Returns `true` if the daemon closes the connection after `res`.
+fn closes_connection(res: &Response) -> bool {
+    res.headers()
+        .get_all("Connection")
+        .flat_map(|value| value.split(','))
+        .any(|option| option.trim().eq_ignore_ascii_case("close"))
+}
+
+/// Send a request on `socket` and read the whole response.
+fn send(
+    socket: &UnixStream,
+    req: Request,
+    timeouts: &Timeouts,
+    limits: &Limits,
+) -> Result<Response> {
+    socket.set_write_timeout(timeouts.write)?;
+    socket.set_read_timeout(timeouts.read)?;
+
+    let head = req.method() == Method::Head;
+    write_request(socket, req)?;
+    await_response(socket)?;
+
+    if head {
+        Response::head(socket, limits).map_err(Into::into)
+    } else {
+        Response::read_from(socket, limits).map_err(Into::into)
+    }
+}
+
+/// Send a request on `socket`, leaving the body of the response unread.
+fn send_streaming(
+    socket: UnixStream,
+    req: Request,
+    timeouts: &Timeouts,
+    limits: &Limits,
+) -> Result<(Response, Body)> {
+    socket.set_write_timeout(timeouts.write)?;
+    socket.set_read_timeout(timeouts.read)?;
+
+    write_request(&socket, req)?;
+    await_response(&socket)?;
+
+    let control = socket.try_clone()?;
+    let (res, body) = Response::stream(socket, limits)?;
+    control.set_read_timeout(timeouts.stream_idle)?;
+
+    Ok((res, body))
+}
+
+/// Write `req` to `socket`.
+///
+/// The daemon ignores a request it did not fully receive, so a request interrupted by the
+/// connection being closed can be sent again.
+fn write_request(socket: &UnixStream, req: Request) -> Result<()> {
+    req.write_to(BufWriter::new(socket))
+        .map_err(|err| match is_closed(err.kind()) {
+            true => Error::from(err).unanswered(),
+            false => err.into(),
+        })
+}
+
+/// Wait until the daemon starts answering on `socket`, without reading anything.
+///
+/// Fails if the connection is closed before any of the response is received, e.g. because the
+/// daemon restarted. Once the daemon has started answering, it has processed the request, so a
+/// later failure does not allow sending it again.
+fn await_response(socket: &UnixStream) -> Result<()> {
+    let mut buf = [MaybeUninit::uninit()];
+    loop {
+        match SockRef::from(socket).peek(&mut buf) {
+            Ok(0) => {
+                let err = io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "connection closed before receiving a response",
+                );
+                return Err(Error::from(err).unanswered());
+            }
+            Ok(_) => return Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) if is_closed(err.kind()) => return Err(Error::from(err).unanswered()),
+            Err(err) => return Err(err.into()),
         }
     }
 }
 
-pub(crate) struct Client<S> {
-    socket: S,
+/// Returns `true` if an operation failed because the daemon closed the connection.
+fn is_closed(kind: io::ErrorKind) -> bool {
+    matches!(
+        kind,
+        io::ErrorKind::BrokenPipe
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+    )
 }
 
-impl Client<UnixStream> {
-    fn request<S>(&self, req: Request<S>) -> Result<Response>
-    where
-        S: Serialize,
-    {
-        let mut socket = self.socket.try_clone()?;
+/// Connect to the unix socket at `path`, giving up after `timeout`.
+///
+/// Connecting to a unix socket either completes or fails at once, unless the backlog of the
+/// listener is full. A non-blocking connection then fails with `WouldBlock` instead of waiting,
+/// so it is attempted again until it completes or `timeout` elapses.
+fn connect(path: &str, timeout: Option<Duration>) -> Result<UnixStream> {
+    let Some(timeout) = timeout else {
+        return Ok(UnixStream::connect(path)?);
+    };
 
-        let buf = req.into_bytes();
-        socket.write_all(&buf)?;
+    let deadline = Instant::now() + timeout;
+    let address = SockAddr::unix(path)?;
+    let socket = Socket::new(Domain::UNIX, Type::STREAM, None)?;
+    socket.set_nonblocking(true)?;
 
-        Response::try_from(BufReader::new(socket)).map_err(Into::into)
+    loop {
+        match socket.connect(&address) {
+            Ok(()) => break,
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        format!("timed out connecting to {}", path),
+                    )
+                    .into());
+                }
+                thread::sleep(CONNECT_RETRY_INTERVAL.min(deadline - now));
+            }
+            Err(err) => return Err(err.into()),
+        }
     }
+
+    socket.set_nonblocking(false)?;
+    Ok(UnixStream::from(OwnedFd::from(socket)))
 }
 
+/// The interval between two attempts to connect to a listener whose backlog is full.
+const CONNECT_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
 impl std::fmt::Debug for Transport {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -62,3 +257,143 @@ impl std::fmt::Debug for Transport {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixListener;
+    use std::sync::mpsc::{self, Receiver, Sender};
+
+    use shiprs_http::RequestBuilder;
+
+    use super::*;
+    use crate::error::ErrorKind;
+
+    /// Answer a single request on each connection accepted by `listener`, then close it.
+    ///
+    /// The index of the connection is sent to `received` once its request is read, and the
+    /// first connection only answers once `release` receives something.
+    fn serve(listener: UnixListener, received: Sender<usize>, release: Receiver<()>) {
+        thread::spawn(move || {
+            let mut release = Some(release);
+            for (index, socket) in listener.incoming().enumerate() {
+                let Ok(mut socket) = socket else { break };
+                let received = received.clone();
+                let release = release.take();
+                thread::spawn(move || {
+                    let mut reader = BufReader::new(socket.try_clone().unwrap());
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).unwrap() > 2 {
+                        line.clear();
+                    }
+                    received.send(index).unwrap();
+
+                    if let Some(release) = release {
+                        release.recv().unwrap();
+                    }
+                    socket
+                        .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nOK")
+                        .unwrap();
+                });
+            }
+        });
+    }
+
+    fn listen(name: &str) -> (UnixListener, String) {
+        let path =
+            std::env::temp_dir().join(format!("shiprs-{}-{}.sock", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        (listener, path.to_str().unwrap().to_string())
+    }
+
+    fn ping() -> Request<'static> {
+        RequestBuilder::<(), ()>::get("/_ping").build().unwrap()
+    }
+
+    #[test]
+    fn replace_closed_connections() {
+        let (listener, path) = listen("closed");
+        let (sender, received) = mpsc::channel();
+        let (release, gate) = mpsc::channel();
+        release.send(()).unwrap();
+        serve(listener, sender, gate);
+
+        let timeouts = Timeouts {
+            read: Some(Duration::from_secs(5)),
+            ..Default::default()
+        };
+        let transport = Transport::unix(path.as_str(), &timeouts, Limits::default()).unwrap();
+
+        // The daemon closes each connection after answering it.
+        for index in 0..2 {
+            let res = transport.request(ping(), &timeouts).unwrap();
+            assert_eq!(res.body(), b"OK");
+            assert_eq!(received.recv().unwrap(), index);
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn send_requests_concurrently() {
+        let (listener, path) = listen("concurrent");
+        let (sender, received) = mpsc::channel();
+        let (release, gate) = mpsc::channel();
+        serve(listener, sender, gate);
+
+        let timeouts = Timeouts {
+            read: Some(Duration::from_secs(5)),
+            ..Default::default()
+        };
+        let transport = Transport::unix(path.as_str(), &timeouts, Limits::default()).unwrap();
+
+        thread::scope(|scope| {
+            let pending = scope.spawn(|| transport.request(ping(), &timeouts));
+            assert_eq!(received.recv().unwrap(), 0);
+
+            // The first request is still waiting for its response.
+            let res = transport.request(ping(), &timeouts).unwrap();
+            assert_eq!(res.body(), b"OK");
+            assert_eq!(received.recv().unwrap(), 1);
+
+            release.send(()).unwrap();
+            assert_eq!(pending.join().unwrap().unwrap().body(), b"OK");
+        });
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn connect_with_timeout() {
+        let path = std::env::temp_dir().join(format!("shiprs-connect-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let path = path.to_str().unwrap();
+
+        // A listener which never accepts, with a backlog of a single connection.
+        let listener = Socket::new(Domain::UNIX, Type::STREAM, None).unwrap();
+        listener.bind(&SockAddr::unix(path).unwrap()).unwrap();
+        listener.listen(0).unwrap();
+
+        let timeout = Some(Duration::from_millis(100));
+        let mut pending = Vec::new();
+        let err = loop {
+            match connect(path, timeout) {
+                Ok(connection) => pending.push(connection),
+                Err(err) => break err,
+            }
+            assert!(
+                pending.len() < 16,
+                "the backlog of the listener never filled"
+            );
+        };
+        assert!(!pending.is_empty());
+        assert!(matches!(err.kind(), ErrorKind::Timeout), "{:?}", err);
+
+        let err = connect("/nonexistent/docker.sock", timeout).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::Io), "{:?}", err);
+
+        let _ = std::fs::remove_file(path);
+    }
+}
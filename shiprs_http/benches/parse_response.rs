@@ -0,0 +1,100 @@
+//! Parsing of large responses, such as the inspection of a container or the listing of thousands
+//! of containers.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use shiprs_http::{Limits, Response};
+
+/// A summary as returned by `GET /containers/json`.
+fn container_summary(i: usize) -> String {
+    format!(
+        r#"{{"Id":"{i:064x}","Names":["/container-{i}"],"Image":"registry.example.com/team/service:1.2.{i}","ImageID":"sha256:{i:064x}","Command":"/usr/local/bin/entrypoint.sh --serve","Created":1718000000,"Ports":[{{"PrivatePort":8080,"PublicPort":{port},"Type":"tcp"}}],"Labels":{{"com.example.team":"inventory","com.example.index":"{i}"}},"State":"running","Status":"Up 3 days","HostConfig":{{"NetworkMode":"default"}},"NetworkSettings":{{"Networks":{{"bridge":{{"IPAddress":"172.17.{a}.{b}","Gateway":"172.17.0.1","MacAddress":"02:42:ac:11:00:02"}}}}}},"Mounts":[]}}"#,
+        port = 30000 + i % 30000,
+        a = i / 256 % 256,
+        b = i % 256,
+    )
+}
+
+fn list_body(containers: usize) -> Vec<u8> {
+    let summaries = (0..containers).map(container_summary).collect::<Vec<_>>();
+    format!("[{}]", summaries.join(",")).into_bytes()
+}
+
+/// A container inspection with a large environment and many labels.
+fn inspect_body() -> Vec<u8> {
+    let env = (0..2000)
+        .map(|i| format!(r#""VARIABLE_{i}=value-{i}""#))
+        .collect::<Vec<_>>()
+        .join(",");
+    let labels = (0..2000)
+        .map(|i| format!(r#""com.example.label.{i}":"value-{i}""#))
+        .collect::<Vec<_>>()
+        .join(",");
+    format!(
+        r#"{{"Id":"{id:064x}","Name":"/inventory","Config":{{"Env":[{env}],"Labels":{{{labels}}}}}}}"#,
+        id = 42
+    )
+    .into_bytes()
+}
+
+const HEAD: &str = "HTTP/1.1 200 OK\r\nApi-Version: 1.44\r\nContent-Type: application/json\r\nDocker-Experimental: false\r\nOstype: linux\r\nServer: Docker/25.0.3 (linux)\r\nDate: Tue, 11 Jun 2024 10:00:00 GMT\r\n";
+
+fn with_length(body: &[u8]) -> Vec<u8> {
+    let mut response = format!("{}Content-Length: {}\r\n\r\n", HEAD, body.len()).into_bytes();
+    response.extend_from_slice(body);
+    response
+}
+
+fn chunked(body: &[u8]) -> Vec<u8> {
+    let mut response = format!("{}Transfer-Encoding: chunked\r\n\r\n", HEAD).into_bytes();
+    for chunk in body.chunks(32 * 1024) {
+        response.extend_from_slice(format!("{:X}\r\n", chunk.len()).as_bytes());
+        response.extend_from_slice(chunk);
+        response.extend_from_slice(b"\r\n");
+    }
+    response.extend_from_slice(b"0\r\n\r\n");
+    response
+}
+
+fn parse(c: &mut Criterion) {
+    let payloads = [
+        ("inspect", inspect_body()),
+        ("list_100", list_body(100)),
+        ("list_5000", list_body(5000)),
+    ];
+
+    let mut group = c.benchmark_group("parse_response");
+    for (name, body) in &payloads {
+        group.throughput(Throughput::Bytes(body.len() as u64));
+
+        let response = with_length(body);
+        group.bench_with_input(
+            BenchmarkId::new("length", name),
+            &response,
+            |b, response| {
+                b.iter(|| {
+                    Response::read_from(black_box(&response[..]), &Limits::default()).unwrap()
+                })
+            },
+        );
+
+        let response = chunked(body);
+        group.bench_with_input(
+            BenchmarkId::new("chunked", name),
+            &response,
+            |b, response| {
+                b.iter(|| {
+                    Response::read_from(black_box(&response[..]), &Limits::default()).unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+
+    c.bench_function("parse_response/head_only", |b| {
+        let response = with_length(b"");
+        b.iter(|| Response::read_from(black_box(&response[..]), &Limits::default()).unwrap())
+    });
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -0,0 +1,111 @@
+use std::io::{self, Read, Write};
+
+use crate::io::ChunkedWriter;
+
+/// The body of a request.
+#[derive(Default)]
+pub enum Body<'a> {
+    #[default]
+    Empty,
+    /// A serialized JSON value, sent with a `Content-Length`.
+    Json(Vec<u8>),
+    /// Raw bytes, sent with a `Content-Length`.
+    Bytes(Vec<u8>),
+    /// A body of unknown length, read until the end of the reader and sent using the
+    /// `chunked` transfer coding.
+    Stream(Box<dyn Read + 'a>),
+}
+
+impl Body<'_> {
+    /// The length of the body, if it is known before it is sent.
+    pub fn len(&self) -> Option<usize> {
+        match self {
+            Body::Empty => Some(0),
+            Body::Json(bytes) | Body::Bytes(bytes) => Some(bytes.len()),
+            Body::Stream(_) => None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Body::Empty)
+    }
+
+    /// A copy of the body, unless it is a stream which can only be read once.
+    pub fn try_clone(&self) -> Option<Body<'static>> {
+        match self {
+            Body::Empty => Some(Body::Empty),
+            Body::Json(bytes) => Some(Body::Json(bytes.clone())),
+            Body::Bytes(bytes) => Some(Body::Bytes(bytes.clone())),
+            Body::Stream(_) => None,
+        }
+    }
+
+    /// Write the body to `writer`, encoding it with the `chunked` transfer coding if it is a
+    /// stream.
+    pub(crate) fn write_to<W: Write>(self, mut writer: W) -> io::Result<()> {
+        match self {
+            Body::Empty => Ok(()),
+            Body::Json(bytes) | Body::Bytes(bytes) => writer.write_all(&bytes),
+            Body::Stream(mut reader) => {
+                let mut chunked = ChunkedWriter::new(writer);
+                io::copy(&mut reader, &mut chunked)?;
+                chunked.finish().map(|_| ())
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for Body<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Body::Empty => f.write_str("Empty"),
+            Body::Json(bytes) => f
+                .debug_tuple("Json")
+                .field(&String::from_utf8_lossy(bytes))
+                .finish(),
+            Body::Bytes(bytes) => write!(f, "Bytes({} bytes)", bytes.len()),
+            Body::Stream(_) => f.write_str("Stream"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_stream_body() -> io::Result<()> {
+        let reader: &[u8] = b"Wikipedia";
+        let body = Body::Stream(Box::new(reader));
+        assert_eq!(body.len(), None);
+
+        let mut buf = Vec::new();
+        body.write_to(&mut buf)?;
+
+        assert_eq!(buf, b"9\r\nWikipedia\r\n0\r\n\r\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_clone_body() {
+        let body = Body::Json(b"{}".to_vec()).try_clone();
+        assert!(matches!(body, Some(Body::Json(bytes)) if bytes == b"{}"));
+
+        let reader: &[u8] = b"Wikipedia";
+        assert!(Body::Stream(Box::new(reader)).try_clone().is_none());
+    }
+
+    #[test]
+    fn test_write_bytes_body() -> io::Result<()> {
+        let body = Body::Bytes(b"Wikipedia".to_vec());
+        assert_eq!(body.len(), Some(9));
+
+        let mut buf = Vec::new();
+        body.write_to(&mut buf)?;
+
+        assert_eq!(buf, b"Wikipedia");
+
+        Ok(())
+    }
+}
@@ -17,6 +17,10 @@ impl Error {
         }
     }
 
+    pub fn kind(&self) -> &ErrorKind {
+        &self.inner.kind
+    }
+
     pub(crate) fn with<C: Into<Cause>>(mut self, cause: C) -> Error {
         self.inner.cause = Some(cause.into());
         self
@@ -34,9 +38,14 @@ pub enum ErrorKind {
     SerdeJson,
     Io,
     HttpParsing(HttpParsingKind),
+    /// A request path could not be built, see [`PathTemplate`](crate::uri::PathTemplate).
+    InvalidPath,
+    /// A request header has a name which is not a token, or a value holding a `CR`, `LF` or `NUL`
+    /// byte, see [`HeaderMap::validate`](crate::HeaderMap::validate).
+    InvalidHeader,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HttpParsingKind {
     Version,
     Status,
@@ -45,6 +54,15 @@ pub enum HttpParsingKind {
     ChunkSize,
     Chunk,
     ContentLength,
+    /// The status line and headers, or the trailers, are larger than
+    /// [`Limits::max_head_size`](crate::Limits::max_head_size).
+    HeadTooLarge,
+    /// There are more headers than [`Limits::max_headers`](crate::Limits::max_headers).
+    TooManyHeaders,
+    /// A chunk is larger than [`Limits::max_chunk_size`](crate::Limits::max_chunk_size).
+    ChunkTooLarge,
+    /// The body is larger than [`Limits::max_body_size`](crate::Limits::max_body_size).
+    BodyTooLarge,
 }
 
 impl std::fmt::Debug for Error {
@@ -60,6 +78,14 @@ impl std::fmt::Debug for Error {
 
 impl From<std::io::Error> for Error {
     fn from(err: std::io::Error) -> Self {
+        // Parsing errors raised while reading a body are carried through `std::io::Error`.
+        if err.get_ref().is_some_and(|e| e.is::<Error>()) {
+            let inner = err.into_inner().expect("io error has an inner error");
+            return *inner
+                .downcast::<Error>()
+                .expect("inner error is an `Error`");
+        }
+
         Error::new(ErrorKind::Io).with(err)
     }
 }
@@ -97,6 +123,8 @@ impl std::fmt::Display for Error {
             SerdeUrlEncoded => write!(f, "serde_urlencoded error: {}", self.source().unwrap()),
             SerdeJson => write!(f, "serde_json error: {}", self.source().unwrap()),
             HttpParsing(ref kind) => write!(f, "http parsing error: {:?}", kind),
+            InvalidPath => write!(f, "invalid path: {}", self.source().unwrap()),
+            InvalidHeader => write!(f, "invalid header: {}", self.source().unwrap()),
         }
     }
 }
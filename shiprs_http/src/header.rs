@@ -0,0 +1,254 @@
+use crate::error::{Error, ErrorKind, HttpParsingKind::*, Result};
+
+/// The headers of a request or a response.
+///
+/// Header names are compared case-insensitively, as required by HTTP, and a name may hold
+/// several values, e.g. when a header is repeated. The headers keep the order they were added in.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeaderMap {
+    entries: Vec<(String, String)>,
+}
+
+impl HeaderMap {
+    pub fn new() -> Self {
+        HeaderMap::default()
+    }
+
+    /// The first value of the header `name`.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// All the values of the header `name`, in order.
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.entries
+            .iter()
+            .filter(move |(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Set the header `name` to `value`, replacing all its previous values.
+    pub fn insert<K, V>(&mut self, name: K, value: V)
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let name = name.into();
+        self.remove(&name);
+        self.entries.push((name, value.into()));
+    }
+
+    /// Add `value` to the values of the header `name`.
+    pub fn append<K, V>(&mut self, name: K, value: V)
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.entries.push((name.into(), value.into()));
+    }
+
+    /// Remove all the values of the header `name`, returning the first one.
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        let mut removed = None;
+        self.entries.retain_mut(|(key, value)| {
+            if !key.eq_ignore_ascii_case(name) {
+                return true;
+            }
+            if removed.is_none() {
+                removed = Some(std::mem::take(value));
+            }
+            false
+        });
+        removed
+    }
+
+    /// The headers as `(name, value)` pairs, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The value of the `Content-Length` header.
+    ///
+    /// Fails if the value is not a number, or if the header is repeated with different values.
+    pub fn content_length(&self) -> Result<Option<usize>> {
+        let mut length = None;
+        for value in self.get_all("Content-Length") {
+            let value = value
+                .trim()
+                .parse()
+                .map_err::<Error, _>(|_| ContentLength.into())?;
+            match length {
+                Some(length) if length != value => return Err(ContentLength.into()),
+                _ => length = Some(value),
+            }
+        }
+
+        Ok(length)
+    }
+
+    /// Returns `true` if `chunked` is the last transfer coding applied to the body.
+    pub fn is_chunked(&self) -> bool {
+        self.get_all("Transfer-Encoding")
+            .flat_map(|value| value.split(','))
+            .last()
+            .is_some_and(|coding| coding.trim().eq_ignore_ascii_case("chunked"))
+    }
+
+    /// The value of the `Content-Type` header.
+    pub fn content_type(&self) -> Option<&str> {
+        self.get("Content-Type")
+    }
+
+    /// Check that the headers can be sent as they are.
+    ///
+    /// Fails if a name is not a token, or if a value holds a `CR`, `LF` or `NUL` byte, which would
+    /// let it end the header early and add other headers, or even another request.
+    pub fn validate(&self) -> Result<()> {
+        for (name, value) in self.iter() {
+            if name.is_empty() || !name.bytes().all(is_token) {
+                return Err(Error::new(ErrorKind::InvalidHeader)
+                    .with(format!("{:?} is not a valid header name", name)));
+            }
+            if value.bytes().any(|b| matches!(b, b'\r' | b'\n' | b'\0')) {
+                return Err(Error::new(ErrorKind::InvalidHeader)
+                    .with(format!("the value of {} is not valid: {:?}", name, value)));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns `true` if `b` may appear in a header name.
+#[inline]
+pub(crate) fn is_token(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+}
+
+impl std::ops::Index<&str> for HeaderMap {
+    type Output = str;
+
+    /// The first value of the header `name`.
+    ///
+    /// # Panics
+    /// Panics if the header is not set.
+    fn index(&self, name: &str) -> &str {
+        self.get(name)
+            .unwrap_or_else(|| panic!("no header named {:?}", name))
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for HeaderMap
+where
+    K: Into<String>,
+    V: Into<String>,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.extend(iter);
+        headers
+    }
+}
+
+impl<K, V> Extend<(K, V)> for HeaderMap
+where
+    K: Into<String>,
+    V: Into<String>,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (name, value) in iter {
+            self.append(name, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_insensitive_lookup() {
+        let headers = HeaderMap::from_iter([("content-length", "12"), ("X-Custom", "a")]);
+
+        assert_eq!(headers.get("Content-Length"), Some("12"));
+        assert_eq!(headers.get("CONTENT-LENGTH"), Some("12"));
+        assert_eq!(&headers["x-custom"], "a");
+        assert!(!headers.contains("Content-Type"));
+    }
+
+    #[test]
+    fn test_multiple_values() {
+        let mut headers = HeaderMap::new();
+        headers.append("Warning", "first");
+        headers.append("warning", "second");
+
+        assert_eq!(headers.get("Warning"), Some("first"));
+        assert_eq!(
+            headers.get_all("WARNING").collect::<Vec<_>>(),
+            vec!["first", "second"]
+        );
+
+        headers.insert("Warning", "only");
+        assert_eq!(headers.get_all("Warning").collect::<Vec<_>>(), vec!["only"]);
+
+        assert_eq!(headers.remove("warning"), Some("only".to_string()));
+        assert!(headers.is_empty());
+    }
+
+    #[test]
+    fn test_typed_accessors() -> Result<()> {
+        let headers = HeaderMap::from_iter([
+            ("transfer-encoding", "gzip, Chunked"),
+            ("content-type", "application/json"),
+        ]);
+        assert!(headers.is_chunked());
+        assert_eq!(headers.content_type(), Some("application/json"));
+        assert_eq!(headers.content_length()?, None);
+
+        let headers = HeaderMap::from_iter([("Content-Length", "42"), ("content-length", "42")]);
+        assert_eq!(headers.content_length()?, Some(42));
+        assert!(!headers.is_chunked());
+
+        let headers = HeaderMap::from_iter([("Content-Length", "42"), ("Content-Length", "7")]);
+        assert!(headers.content_length().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate() {
+        let headers = HeaderMap::from_iter([("User-Agent", "shiprs/0.1"), ("X-Empty", "")]);
+        assert!(headers.validate().is_ok());
+
+        for (name, value) in [
+            ("X-Origin", "tests\r\nX-Injected: 1"),
+            ("X-Origin", "tests\n"),
+            ("X-Origin", "te\0sts"),
+            ("X Origin", "tests"),
+            ("X-Origin:", "tests"),
+            ("", "tests"),
+        ] {
+            let err = HeaderMap::from_iter([(name, value)])
+                .validate()
+                .unwrap_err();
+            assert!(matches!(err.kind(), ErrorKind::InvalidHeader), "{:?}", err);
+        }
+    }
+}
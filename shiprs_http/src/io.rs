@@ -1,38 +1,501 @@
-use std::io::{BufRead, BufReader, Read};
+use std::io::{BufRead, Error as IoError, ErrorKind, Read, Result as IoResult, Write};
 
-pub struct Reader<R> {
-    pub(crate) reader: BufReader<R>,
+use crate::bytes::Bytes;
+use crate::error::{Error, HttpParsingKind::*};
+use crate::header::HeaderMap;
+use crate::limits::Limits;
+use crate::response::{parse_headers, BodyKind};
+use crate::CRLF;
+
+/// The maximum length of the line giving the size of a chunk, including its extensions.
+const MAX_CHUNK_SIZE_LINE: usize = 4096;
+
+/// A reader over an HTTP message body.
+///
+/// The transfer encoding is decoded on the fly, so the body never needs to be held in memory as a
+/// whole. Reading returns `Ok(0)` once the end of the body has been reached.
+pub struct BodyReader<B> {
+    inner: B,
+    state: State,
+    limits: Limits,
+    trailers: HeaderMap,
 }
 
-impl<R> Read for Reader<R>
+#[derive(Debug, Clone, Copy)]
+enum State {
+    /// Remaining bytes of a `Content-Length` body.
+    Length(usize),
+    /// Expecting the size line of the next chunk.
+    ChunkSize,
+    /// Remaining bytes of the current chunk.
+    Chunk(usize),
+    Done,
+}
+
+impl<B> BodyReader<B>
+where
+    B: BufRead,
+{
+    pub(crate) fn new(inner: B, kind: BodyKind, limits: &Limits) -> Self {
+        let state = match kind {
+            BodyKind::Chunked => State::ChunkSize,
+            BodyKind::Length(length) => State::Length(length),
+            BodyKind::Empty => State::Done,
+        };
+
+        BodyReader {
+            inner,
+            state,
+            limits: *limits,
+            trailers: HeaderMap::new(),
+        }
+    }
+
+    /// Returns `true` once the whole body has been read.
+    pub fn is_done(&self) -> bool {
+        matches!(self.state, State::Done | State::Length(0))
+    }
+
+    /// The trailer fields sent after the last chunk of a `chunked` body.
+    ///
+    /// They are only known once the whole body has been read, and are always empty for other
+    /// bodies.
+    pub fn trailers(&self) -> &HeaderMap {
+        &self.trailers
+    }
+
+    /// Unwraps this `BodyReader`, returning the underlying reader.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    /// Read the line starting a chunk, made of the chunk size and optional chunk extensions,
+    /// which are ignored.
+    fn read_chunk_size(&mut self) -> IoResult<usize> {
+        let mut line = Vec::new();
+        (&mut self.inner)
+            .take(MAX_CHUNK_SIZE_LINE as u64)
+            .read_until(b'\n', &mut line)?;
+
+        let line = line
+            .strip_suffix(CRLF)
+            .ok_or_else(|| parsing_error(ChunkSize))?;
+        let size = match line.iter().position(|&b| b == b';') {
+            Some(extensions) => trim_whitespace_end(&line[..extensions]),
+            None => line,
+        };
+        if size.is_empty() || !size.iter().all(u8::is_ascii_hexdigit) {
+            return Err(parsing_error(ChunkSize));
+        }
+
+        // SAFETY: the size only holds hexadecimal digits, which are US-ASCII.
+        let size = unsafe { std::str::from_utf8_unchecked(size) };
+        let size = usize::from_str_radix(size, 16).map_err(|_| parsing_error(ChunkSize))?;
+        if size > self.limits.max_chunk_size {
+            return Err(parsing_error(ChunkTooLarge));
+        }
+
+        Ok(size)
+    }
+
+    /// Read the trailer section following the last chunk, ended by an empty line.
+    fn read_trailers(&mut self) -> IoResult<()> {
+        let max = self.limits.max_head_size;
+        let mut section = Vec::new();
+
+        loop {
+            let start = section.len();
+            (&mut self.inner)
+                .take((max - start) as u64)
+                .read_until(b'\n', &mut section)?;
+
+            match &section[start..] {
+                b"\r\n" | b"\n" => break,
+                line if line.ends_with(b"\n") => continue,
+                _ if section.len() >= max => return Err(parsing_error(HeadTooLarge)),
+                _ => return Err(unexpected_eof()),
+            }
+        }
+
+        let mut bytes = Bytes::new(&section);
+        parse_headers(&mut bytes, &mut self.trailers, self.limits.max_headers)
+            .map_err(|err| IoError::new(ErrorKind::InvalidData, err))?;
+
+        Ok(())
+    }
+
+    fn read_crlf(&mut self) -> IoResult<()> {
+        let mut crlf = [0; 2];
+        self.inner
+            .read_exact(&mut crlf)
+            .map_err(|_| parsing_error(Chunk))?;
+
+        if crlf != CRLF {
+            return Err(parsing_error(Chunk));
+        }
+
+        Ok(())
+    }
+}
+
+impl<B> Read for BodyReader<B>
+where
+    B: BufRead,
+{
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        loop {
+            match self.state {
+                State::Done | State::Length(0) => return Ok(0),
+                State::Length(remaining) => {
+                    let max = buf.len().min(remaining);
+                    let n = self.inner.read(&mut buf[..max])?;
+                    if n == 0 {
+                        return Err(unexpected_eof());
+                    }
+
+                    self.state = State::Length(remaining - n);
+                    return Ok(n);
+                }
+                State::ChunkSize => match self.read_chunk_size()? {
+                    0 => {
+                        self.read_trailers()?;
+                        self.state = State::Done;
+                    }
+                    size => self.state = State::Chunk(size),
+                },
+                State::Chunk(remaining) => {
+                    let max = buf.len().min(remaining);
+                    let n = self.inner.read(&mut buf[..max])?;
+                    if n == 0 {
+                        return Err(parsing_error(Chunk));
+                    }
+
+                    if remaining == n {
+                        // Read the CRLF closing the chunk.
+                        self.read_crlf()?;
+                        self.state = State::ChunkSize;
+                    } else {
+                        self.state = State::Chunk(remaining - n);
+                    }
+                    return Ok(n);
+                }
+            }
+        }
+    }
+}
+
+/// The initial capacity of a [`ReadBuffer`], enough for the head of most responses.
+const INITIAL_CAPACITY: usize = 8 * 1024;
+
+/// A buffered reader whose buffer grows to hold a whole response head.
+///
+/// Unlike [`std::io::BufReader`], the buffered bytes can be extended until a complete head has
+/// been read, so that it can be parsed in place. Reads larger than the buffer bypass it once it
+/// has been drained, so bodies are read in large blocks straight into their destination.
+pub struct ReadBuffer<R> {
+    inner: R,
+    buf: Vec<u8>,
+    pos: usize,
+    filled: usize,
+}
+
+impl<R> ReadBuffer<R>
+where
+    R: Read,
+{
+    pub fn new(inner: R) -> Self {
+        ReadBuffer {
+            inner,
+            buf: vec![0; INITIAL_CAPACITY],
+            pos: 0,
+            filled: 0,
+        }
+    }
+
+    /// The bytes read from the underlying reader but not consumed yet.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf[self.pos..self.filled]
+    }
+
+    /// Read more bytes from the underlying reader and append them to the buffered bytes,
+    /// growing the buffer if it is full.
+    ///
+    /// Returns the number of bytes read, `0` meaning that the end of the reader has been reached.
+    pub(crate) fn fill_more(&mut self) -> IoResult<usize> {
+        if self.pos > 0 {
+            self.buf.copy_within(self.pos..self.filled, 0);
+            self.filled -= self.pos;
+            self.pos = 0;
+        }
+        if self.filled == self.buf.len() {
+            self.buf.resize(self.buf.len() * 2, 0);
+        }
+
+        let n = self.inner.read(&mut self.buf[self.filled..])?;
+        self.filled += n;
+        Ok(n)
+    }
+
+    /// Unwraps this `ReadBuffer`, returning the underlying reader.
+    /// The buffered bytes are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> Read for ReadBuffer<R>
 where
     R: Read,
 {
-    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        self.reader.read(buf)
+    fn read(&mut self, out: &mut [u8]) -> IoResult<usize> {
+        if self.pos == self.filled && out.len() >= self.buf.len() {
+            return self.inner.read(out);
+        }
+
+        let available = self.fill_buf()?;
+        let n = available.len().min(out.len());
+        out[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
     }
 }
 
-impl<R> BufRead for Reader<R>
+impl<R> BufRead for ReadBuffer<R>
 where
     R: Read,
 {
-    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
-        self.reader.fill_buf()
+    fn fill_buf(&mut self) -> IoResult<&[u8]> {
+        if self.pos == self.filled {
+            self.pos = 0;
+            self.filled = self.inner.read(&mut self.buf)?;
+        }
+
+        Ok(self.buffer())
     }
 
     fn consume(&mut self, amt: usize) {
-        self.reader.consume(amt)
+        self.pos = (self.pos + amt).min(self.filled);
     }
 }
 
-impl<R> From<R> for Reader<R>
+/// A writer encoding everything written to it with the `chunked` transfer coding.
+///
+/// Each call to `write` emits one chunk. [`ChunkedWriter::finish`] must be called to write the
+/// last chunk once the whole body has been written.
+pub struct ChunkedWriter<W> {
+    inner: W,
+}
+
+impl<W> ChunkedWriter<W>
 where
-    R: Read,
+    W: Write,
 {
-    fn from(reader: R) -> Self {
-        Reader {
-            reader: BufReader::new(reader),
+    pub fn new(inner: W) -> Self {
+        ChunkedWriter { inner }
+    }
+
+    /// Writes the last chunk and returns the underlying writer.
+    pub fn finish(mut self) -> IoResult<W> {
+        self.inner.write_all(b"0\r\n\r\n")?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W> Write for ChunkedWriter<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        // An empty chunk would mark the end of the body.
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        write!(self.inner, "{:X}\r\n", buf.len())?;
+        self.inner.write_all(buf)?;
+        self.inner.write_all(CRLF)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        self.inner.flush()
+    }
+}
+
+fn trim_whitespace_end(mut bytes: &[u8]) -> &[u8] {
+    while let [rest @ .., b' ' | b'\t'] = bytes {
+        bytes = rest;
+    }
+    bytes
+}
+
+fn parsing_error(kind: crate::error::HttpParsingKind) -> IoError {
+    IoError::new(ErrorKind::InvalidData, Error::from(kind))
+}
+
+fn unexpected_eof() -> IoError {
+    IoError::new(ErrorKind::UnexpectedEof, "Unexpected EOF")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_length_body() -> IoResult<()> {
+        let bytes: &[u8] = b"Hello, World!trailing";
+        let mut reader = BodyReader::new(bytes, BodyKind::Length(13), &Limits::default());
+
+        let mut body = Vec::new();
+        reader.read_to_end(&mut body)?;
+
+        assert_eq!(body, b"Hello, World!");
+        assert!(reader.is_done());
+        assert_eq!(reader.into_inner(), b"trailing");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_chunked_body_in_small_reads() -> IoResult<()> {
+        let bytes: &[u8] = b"4\r\nWiki\r\n7\r\npedia i\r\nB\r\nn \r\nchunks.\r\n0\r\n\r\n";
+        let mut reader = BodyReader::new(bytes, BodyKind::Chunked, &Limits::default());
+
+        let mut body = Vec::new();
+        let mut buf = [0; 3];
+        loop {
+            match reader.read(&mut buf)? {
+                0 => break,
+                n => body.extend_from_slice(&buf[..n]),
+            }
         }
+
+        assert_eq!(body, b"Wikipedia in \r\nchunks.");
+        assert!(reader.is_done());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_truncated_length_body() {
+        let bytes: &[u8] = b"Hello";
+        let mut reader = BodyReader::new(bytes, BodyKind::Length(13), &Limits::default());
+
+        let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_read_invalid_chunk_size() {
+        let bytes: &[u8] = b"zz\r\nWiki\r\n0\r\n\r\n";
+        let mut reader = BodyReader::new(bytes, BodyKind::Chunked, &Limits::default());
+
+        let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_read_buffer_grows_until_filled() -> IoResult<()> {
+        let bytes = vec![b'a'; INITIAL_CAPACITY * 3];
+        let mut reader = ReadBuffer::new(&bytes[..]);
+
+        while reader.fill_more()? > 0 {}
+
+        assert_eq!(reader.buffer(), &bytes[..]);
+
+        reader.consume(10);
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest)?;
+        assert_eq!(rest.len(), bytes.len() - 10);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_buffer_keeps_unconsumed_bytes() -> IoResult<()> {
+        let mut reader = ReadBuffer::new(&b"HTTP/1.1 200 OK\r\n\r\nbody"[..]);
+        reader.fill_more()?;
+        reader.consume(19);
+
+        reader.fill_more()?;
+        assert_eq!(reader.buffer(), b"body");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_chunked_body_with_extensions_and_trailers() -> IoResult<()> {
+        let bytes: &[u8] = b"4;name=value\r\nWiki\r\n5 ; quoted=\"a;b\" ; flag\r\npedia\r\n0;last\r\nExpires: never\r\nChecksum: abc\r\n\r\ntrailing";
+        let mut reader = BodyReader::new(bytes, BodyKind::Chunked, &Limits::default());
+
+        let mut body = Vec::new();
+        reader.read_to_end(&mut body)?;
+
+        assert_eq!(body, b"Wikipedia");
+        assert_eq!(reader.trailers().get("expires"), Some("never"));
+        assert_eq!(reader.trailers().get("Checksum"), Some("abc"));
+        assert_eq!(reader.into_inner(), b"trailing");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_invalid_trailers() {
+        let bytes: &[u8] = b"0\r\nNo colon\r\n\r\n";
+        let mut reader = BodyReader::new(bytes, BodyKind::Chunked, &Limits::default());
+
+        let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_read_chunk_over_limit() {
+        let bytes: &[u8] = b"10\r\n0123456789abcdef\r\n0\r\n\r\n";
+        let limits = Limits {
+            max_chunk_size: 15,
+            ..Default::default()
+        };
+        let mut reader = BodyReader::new(bytes, BodyKind::Chunked, &limits);
+
+        let err = Error::from(reader.read_to_end(&mut Vec::new()).unwrap_err());
+        assert!(matches!(
+            err.kind(),
+            crate::error::ErrorKind::HttpParsing(ChunkTooLarge)
+        ));
+    }
+
+    #[test]
+    fn test_read_endless_chunk_size() {
+        let bytes = vec![b'0'; 1024 * 1024];
+        let mut reader = BodyReader::new(&bytes[..], BodyKind::Chunked, &Limits::default());
+
+        let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_chunked_writer_round_trip() -> IoResult<()> {
+        let mut writer = ChunkedWriter::new(Vec::new());
+        writer.write_all(b"Wiki")?;
+        writer.write_all(b"")?;
+        writer.write_all(b"pedia in \r\nchunks.")?;
+        let encoded = writer.finish()?;
+
+        assert_eq!(
+            encoded,
+            b"4\r\nWiki\r\n12\r\npedia in \r\nchunks.\r\n0\r\n\r\n"
+        );
+
+        let mut body = Vec::new();
+        BodyReader::new(&encoded[..], BodyKind::Chunked, &Limits::default())
+            .read_to_end(&mut body)?;
+        assert_eq!(body, b"Wikipedia in \r\nchunks.");
+
+        Ok(())
     }
 }
@@ -1,19 +1,28 @@
 #[macro_use]
 mod macros;
 
+mod body;
 mod bytes;
 mod error;
+mod header;
 mod io;
+mod limits;
 mod method;
 mod request;
 mod response;
-mod uri;
+pub mod uri;
 mod version;
 
 const CRLF: &[u8] = b"\r\n";
 const HEADERS_END: &[u8] = b"\r\n\r\n";
-const DOCKER_API_VERSION: &str = "v1.44";
+/// The `Host` of the requests, which is required by HTTP/1.1 but meaningless on a unix socket.
+const DEFAULT_HOST: &str = "localhost";
 
-pub use error::Error;
+pub use body::Body;
+pub use error::{Error, ErrorKind, HttpParsingKind};
+pub use header::HeaderMap;
+pub use io::{BodyReader, ChunkedWriter, ReadBuffer};
+pub use limits::Limits;
+pub use method::Method;
 pub use request::{Request, RequestBuilder};
 pub use response::Response;
@@ -0,0 +1,32 @@
+/// Maximum sizes accepted when parsing a response, protecting the client from peers sending
+/// endless or oversized responses.
+///
+/// Exceeding a limit fails the parsing with an error of the matching `HttpParsingKind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// Maximum size in bytes of the status line and headers, and of the trailers of a `chunked`
+    /// body, fails with `HeadTooLarge`.
+    pub max_head_size: usize,
+
+    /// Maximum number of headers, fails with `TooManyHeaders`.
+    pub max_headers: usize,
+
+    /// Maximum size in bytes of a chunk of a `chunked` body, fails with `ChunkTooLarge`.
+    pub max_chunk_size: usize,
+
+    /// Maximum size in bytes of a body read into memory, fails with `BodyTooLarge`.
+    /// Bodies read through a [`BodyReader`](crate::BodyReader) are not limited, as they are
+    /// never held in memory as a whole.
+    pub max_body_size: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_head_size: 64 * 1024,
+            max_headers: 100,
+            max_chunk_size: 16 * 1024 * 1024,
+            max_body_size: 256 * 1024 * 1024,
+        }
+    }
+}
@@ -1,25 +1,26 @@
-use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
 
 use serde::Serialize;
 
+use crate::body::Body;
+use crate::error::{Error, Result};
+use crate::header::HeaderMap;
 use crate::method::Method;
-use crate::uri::Uri;
+use crate::uri::{Endpoint, Uri};
+use crate::CRLF;
 
-#[derive(Debug, Clone)]
-pub struct Request<'a, B>
-where
-    B: Serialize,
-{
+#[derive(Debug)]
+pub struct Request<'a> {
     method: Method,
+    endpoint: &'a str,
     uri: Uri<'a>,
-    headers: HashMap<String, String>,
-    body: Option<B>,
+    headers: HeaderMap,
+    body: Body<'a>,
+    idempotent: bool,
 }
 
-impl<'a, B> Request<'a, B>
-where
-    B: Serialize,
-{
+impl<'a> Request<'a> {
     pub fn method(&self) -> Method {
         self.method
     }
@@ -28,38 +29,63 @@ where
         self.uri.as_ref()
     }
 
-    /// Build the request.
-    pub fn build(self) -> String {
-        self.to_string()
+    /// The endpoint the request is sent to, see [`Endpoint`].
+    pub fn endpoint(&self) -> &'a str {
+        self.endpoint
     }
 
-    pub fn into_bytes(self) -> Vec<u8> {
-        self.build().into_bytes()
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
     }
-}
 
-impl<B> std::fmt::Display for Request<'_, B>
-where
-    B: Serialize,
-{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let headers = self
-            .headers
-            .iter()
-            .map(|(k, v)| format!("{}: {}", k, v))
-            .collect::<Vec<_>>()
-            .join("\r\n");
-        write!(
-            f,
-            "{} {} HTTP/1.1\r\n{}\r\n\r\n{}",
-            self.method,
-            self.uri.as_ref(),
-            headers,
-            self.body
-                .as_ref()
-                .map(|b| serde_json::to_string(&b).unwrap())
-                .unwrap_or_default()
-        )
+    pub fn headers_mut(&mut self) -> &mut HeaderMap {
+        &mut self.headers
+    }
+
+    /// Prepend `prefix` to the path of the request, e.g. `/v1.44` to pin the version of the API.
+    /// The [`endpoint`](Request::endpoint) of the request is left unchanged.
+    pub fn prefix_path(&mut self, prefix: &str) {
+        self.uri.prefix(prefix);
+    }
+
+    pub fn body(&self) -> &Body<'a> {
+        &self.body
+    }
+
+    /// Whether sending the request several times has the same effect as sending it once.
+    /// `GET` and `HEAD` requests always are, other requests when built with
+    /// [`RequestBuilder::idempotent`].
+    pub fn is_idempotent(&self) -> bool {
+        self.idempotent || matches!(self.method, Method::Get | Method::Head)
+    }
+
+    /// A copy of the request, unless its body is a stream which can only be read once.
+    pub fn try_clone(&self) -> Option<Request<'a>> {
+        Some(Request {
+            method: self.method,
+            endpoint: self.endpoint,
+            uri: self.uri.clone(),
+            headers: self.headers.clone(),
+            body: self.body.try_clone()?,
+            idempotent: self.idempotent,
+        })
+    }
+
+    /// Write the request to `writer`, streaming the body if it is read from a reader.
+    ///
+    /// Fails without writing anything if the headers are not valid, see [`HeaderMap::validate`].
+    pub fn write_to<W: Write>(self, mut writer: W) -> io::Result<()> {
+        self.headers
+            .validate()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        write!(writer, "{} {} HTTP/1.1\r\n", self.method, self.uri.as_ref())?;
+        for (name, value) in self.headers.iter() {
+            write!(writer, "{}: {}\r\n", name, value)?;
+        }
+        writer.write_all(CRLF)?;
+
+        self.body.write_to(&mut writer)?;
+        writer.flush()
     }
 }
 
@@ -69,10 +95,13 @@ where
     B: Serialize,
 {
     method: Method,
-    path: &'a str,
+    path: Endpoint<'a>,
     query: Option<O>,
-    headers: HashMap<String, String>,
-    body: Option<B>,
+    headers: HeaderMap,
+    body: Body<'a>,
+    idempotent: bool,
+    error: Option<Error>,
+    _body: PhantomData<B>,
 }
 
 impl<'a, O, B> RequestBuilder<'a, O, B>
@@ -83,54 +112,57 @@ where
     /// Create a new request builder.
     /// Prefer using the `get`, `post`, `put`, `delete`, and `head` methods
     /// to create the right request.
-    fn new<P, M>(method: M, path: P) -> Self
+    pub fn new<P, M>(method: M, path: P) -> Self
     where
-        P: Into<&'a str>,
+        P: Into<Endpoint<'a>>,
         M: Into<Method>,
     {
         RequestBuilder {
             method: method.into(),
             path: path.into(),
             query: None,
-            headers: HashMap::from([
-                ("Host".to_string(), crate::DOCKER_API_VERSION.to_string()),
-                ("Content-Type".to_string(), "application/json".to_string()),
+            headers: HeaderMap::from_iter([
+                ("Host", crate::DEFAULT_HOST),
+                ("Content-Type", "application/json"),
             ]),
-            body: None,
+            body: Body::Empty,
+            idempotent: false,
+            error: None,
+            _body: PhantomData,
         }
     }
 
     pub fn get<P>(path: P) -> Self
     where
-        P: Into<&'a str>,
+        P: Into<Endpoint<'a>>,
     {
         RequestBuilder::new(Method::Get, path)
     }
 
     pub fn post<P>(path: P) -> Self
     where
-        P: Into<&'a str>,
+        P: Into<Endpoint<'a>>,
     {
         RequestBuilder::new(Method::Post, path)
     }
 
     pub fn put<P>(path: P) -> Self
     where
-        P: Into<&'a str>,
+        P: Into<Endpoint<'a>>,
     {
         RequestBuilder::new(Method::Put, path)
     }
 
     pub fn delete<P>(path: P) -> Self
     where
-        P: Into<&'a str>,
+        P: Into<Endpoint<'a>>,
     {
         RequestBuilder::new(Method::Delete, path)
     }
 
     pub fn head<P>(path: P) -> Self
     where
-        P: Into<&'a str>,
+        P: Into<Endpoint<'a>>,
     {
         RequestBuilder::new(Method::Head, path)
     }
@@ -149,30 +181,76 @@ where
         self
     }
 
+    /// Send `body` serialized as JSON.
+    ///
+    /// If `body` cannot be serialized, the error is returned by [`build`](RequestBuilder::build).
     pub fn body(mut self, body: B) -> Self {
-        self.body = Some(body);
+        match serde_json::to_vec(&body) {
+            Ok(json) => self.body = Body::Json(json),
+            Err(err) => self.error = Some(err.into()),
+        }
+        self
+    }
+
+    /// Send `bytes` as the body, with the given `Content-Type`.
+    pub fn bytes<C>(mut self, content_type: C, bytes: Vec<u8>) -> Self
+    where
+        C: Into<String>,
+    {
+        self.headers.insert("Content-Type", content_type);
+        self.body = Body::Bytes(bytes);
         self
     }
 
-    pub fn body_mut(&mut self) -> &mut Option<B> {
-        &mut self.body
+    /// Send everything read from `reader` as the body, with the given `Content-Type`.
+    /// As its length is unknown, the body is sent using the `chunked` transfer coding.
+    pub fn stream<C, R>(mut self, content_type: C, reader: R) -> Self
+    where
+        C: Into<String>,
+        R: Read + 'a,
+    {
+        self.headers.insert("Content-Type", content_type);
+        self.body = Body::Stream(Box::new(reader));
+        self
+    }
+
+    /// Mark the request as idempotent, so that it can safely be sent again if it fails,
+    /// see [`Request::is_idempotent`].
+    pub fn idempotent(mut self) -> Self {
+        self.idempotent = true;
+        self
     }
 
     /// Build the request.
-    pub fn build(mut self) -> Request<'a, B> {
-        let uri = Uri::parse(self.path, self.query).unwrap();
-        if self.body.is_some() {
-            let body = serde_json::to_string(&self.body).unwrap();
-            self.headers
-                .insert("Content-Length".to_string(), body.len().to_string());
+    ///
+    /// Fails if the query or the body cannot be serialized, or if a header is not valid, see
+    /// [`HeaderMap::validate`].
+    pub fn build(mut self) -> Result<Request<'a>> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        self.headers.validate()?;
+        let uri = Uri::parse(self.path.path(), self.query)?;
+        match self.body.len() {
+            Some(0) => {}
+            Some(length) => {
+                self.headers.remove("Transfer-Encoding");
+                self.headers.insert("Content-Length", length.to_string());
+            }
+            None => {
+                self.headers.remove("Content-Length");
+                self.headers.insert("Transfer-Encoding", "chunked");
+            }
         }
 
-        Request {
+        Ok(Request {
             method: self.method,
+            endpoint: self.path.template(),
             uri,
             headers: self.headers,
             body: self.body,
-        }
+            idempotent: self.idempotent,
+        })
     }
 }
 
@@ -180,41 +258,103 @@ where
 mod tests {
     use super::*;
 
+    fn write(request: Request) -> String {
+        let mut buf = Vec::new();
+        request.write_to(&mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
     macro_rules! assert_request_uri {
         ($req:expr, $expected:literal) => {{
-            let req_build = $req.clone().build();
+            let req_build = write($req);
             let req_uri = req_build.split("\r\n").next();
             assert_eq!(req_uri, Some($expected));
         }};
     }
 
     #[test]
-    fn build_request_no_options() {
-        let request = RequestBuilder::<String, ()>::get("/containers/json").build();
+    fn build_request_with_endpoint() -> crate::error::Result<()> {
+        let path = crate::uri::PathTemplate::new("/containers/{}/json")
+            .segment("web")
+            .build()?;
+        let request = RequestBuilder::<(), ()>::get(&path).build()?;
+        assert_eq!(request.uri(), "/containers/web/json");
+        assert_eq!(request.endpoint(), "/containers/{}/json");
+
+        let request = RequestBuilder::<_, ()>::get("/containers/json")
+            .query(Some([("all", "true")]))
+            .build()?;
+        assert_eq!(request.uri(), "/containers/json?all=true");
+        assert_eq!(request.endpoint(), "/containers/json");
+
+        Ok(())
+    }
+
+    #[test]
+    fn prefix_request_path() -> crate::error::Result<()> {
+        let mut request = RequestBuilder::<_, ()>::get("/containers/json")
+            .query(Some([("all", "true")]))
+            .build()?;
+        request.prefix_path("/v1.44");
+
+        assert_eq!(request.uri(), "/v1.44/containers/json?all=true");
+        assert_eq!(request.endpoint(), "/containers/json");
+        assert_request_uri!(request, "GET /v1.44/containers/json?all=true HTTP/1.1");
+
+        Ok(())
+    }
+
+    #[test]
+    fn clone_idempotent_request() -> crate::error::Result<()> {
+        let request = RequestBuilder::<(), ()>::get("/containers/json").build()?;
+        assert!(request.is_idempotent());
+
+        let request = RequestBuilder::<(), _>::post("/containers/web/stop")
+            .body(10)
+            .build()?;
+        assert!(!request.is_idempotent());
+
+        let request = RequestBuilder::<(), ()>::post("/containers/web/stop")
+            .idempotent()
+            .header("X-Request-Origin", "tests")
+            .build()?;
+        assert!(request.is_idempotent());
+        let clone = request.try_clone().unwrap();
+        assert!(clone.is_idempotent());
+        assert_eq!(write(clone), write(request));
+
+        let reader: &[u8] = b"Wikipedia";
+        let request = RequestBuilder::<(), ()>::post("/images/load")
+            .stream("application/x-tar", reader)
+            .build()?;
+        assert!(request.try_clone().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn build_request_no_options() -> crate::error::Result<()> {
+        let request = RequestBuilder::<String, ()>::get("/containers/json").build()?;
         assert_eq!(request.method(), Method::Get);
         assert_eq!(request.uri(), "/containers/json");
-        assert_eq!(
-            request.headers.get("Host"),
-            Some(&crate::DOCKER_API_VERSION.to_string())
-        );
+        assert_eq!(request.headers.get("Host"), Some(crate::DEFAULT_HOST));
         assert_eq!(
             request.headers.get("Content-Type"),
-            Some(&String::from("application/json"))
+            Some("application/json")
         );
         assert_request_uri!(request, "GET /containers/json HTTP/1.1");
 
-        let request = RequestBuilder::<String, ()>::post("/containers/create").build();
+        let request = RequestBuilder::<String, ()>::post("/containers/create").build()?;
         assert_eq!(request.method(), Method::Post);
         assert_eq!(request.uri(), "/containers/create");
-        assert_eq!(
-            request.headers.get("Host"),
-            Some(&crate::DOCKER_API_VERSION.to_string())
-        );
+        assert_eq!(request.headers.get("Host"), Some(crate::DEFAULT_HOST));
         assert_eq!(
             request.headers.get("Content-Type"),
-            Some(&String::from("application/json"))
+            Some("application/json")
         );
         assert_request_uri!(request, "POST /containers/create HTTP/1.1");
+
+        Ok(())
     }
 
     #[derive(Serialize)]
@@ -224,17 +364,19 @@ mod tests {
     }
 
     #[test]
-    fn build_request_with_options() {
+    fn build_request_with_options() -> crate::error::Result<()> {
         let options = TestOptions {
             all: true,
             limit: 10,
         };
         let request = RequestBuilder::<TestOptions, ()>::get("/containers/json")
             .query(Some(options))
-            .build();
+            .build()?;
         assert_eq!(request.method(), Method::Get);
         assert_eq!(request.uri(), "/containers/json?all=true&limit=10");
         assert_request_uri!(request, "GET /containers/json?all=true&limit=10 HTTP/1.1");
+
+        Ok(())
     }
 
     #[derive(Debug, Serialize, PartialEq, Clone)]
@@ -244,7 +386,7 @@ mod tests {
     }
 
     #[test]
-    fn build_with_body() {
+    fn build_with_body() -> crate::error::Result<()> {
         let body = TestBody {
             limit: 10,
             test: "test".to_string(),
@@ -254,28 +396,109 @@ mod tests {
 
         let request = RequestBuilder::<(), TestBody>::post("/containers/create")
             .body(body)
-            .build();
+            .build()?;
 
         assert_eq!(request.method(), Method::Post);
         assert_eq!(request.uri(), "/containers/create");
-        assert_request_uri!(request, "POST /containers/create HTTP/1.1");
-
         assert_eq!(
             request.headers.get("Content-Type").unwrap(),
             "application/json"
         );
         assert_eq!(
             request.headers.get("Content-Length").unwrap(),
-            &len.to_string()
+            len.to_string()
         );
 
-        let end = request
-            .build()
-            .chars()
-            .rev()
-            .take(json.len())
-            .collect::<String>();
+        let written = write(request);
+        assert!(written.starts_with("POST /containers/create HTTP/1.1\r\n"));
+
+        let end = written.chars().rev().take(json.len()).collect::<String>();
         let expected_end = r#"{"limit":10,"test":"test"}"#.chars().rev().collect::<String>();
         assert_eq!(end, expected_end);
+
+        Ok(())
+    }
+
+    #[test]
+    fn build_with_stream_body() -> crate::error::Result<()> {
+        let tar: &[u8] = b"Wikipedia";
+        let request = RequestBuilder::<(), ()>::post("/images/load")
+            .header("Content-Length", "42")
+            .stream("application/x-tar", tar)
+            .build()?;
+
+        assert_eq!(
+            request.headers.get("Content-Type"),
+            Some("application/x-tar")
+        );
+        assert_eq!(request.headers.get("Transfer-Encoding"), Some("chunked"));
+        assert!(!request.headers.contains("Content-Length"));
+
+        let written = write(request);
+        assert!(written.starts_with("POST /images/load HTTP/1.1\r\n"));
+        assert!(written.ends_with("\r\n\r\n9\r\nWikipedia\r\n0\r\n\r\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn build_with_bytes_body() -> crate::error::Result<()> {
+        let request = RequestBuilder::<(), ()>::post("/secrets/create")
+            .bytes("text/plain", b"Wikipedia".to_vec())
+            .build()?;
+
+        assert_eq!(request.headers.get("Content-Type"), Some("text/plain"));
+        assert_eq!(request.headers.get("Content-Length"), Some("9"));
+        assert!(write(request).ends_with("\r\n\r\nWikipedia"));
+
+        Ok(())
+    }
+
+    #[derive(Serialize)]
+    struct NestedOptions {
+        filters: TestOptions,
+    }
+
+    #[test]
+    fn build_unserializable_request() {
+        let options = NestedOptions {
+            filters: TestOptions {
+                all: true,
+                limit: 10,
+            },
+        };
+        let err = RequestBuilder::<NestedOptions, ()>::get("/containers/json")
+            .query(Some(options))
+            .build()
+            .unwrap_err();
+        assert!(matches!(err.kind(), crate::ErrorKind::SerdeUrlEncoded));
+
+        let body = std::collections::HashMap::from([((1, 2), "web")]);
+        let err = RequestBuilder::<(), _>::post("/containers/create")
+            .body(body)
+            .build()
+            .unwrap_err();
+        assert!(matches!(err.kind(), crate::ErrorKind::SerdeJson));
+    }
+
+    #[test]
+    fn refuse_invalid_headers() -> crate::error::Result<()> {
+        let err = RequestBuilder::<(), ()>::get("/containers/json")
+            .header("X-Origin", "tests\r\nX-Injected: 1")
+            .build()
+            .unwrap_err();
+        assert!(matches!(err.kind(), crate::ErrorKind::InvalidHeader));
+
+        // Headers set after the request is built are checked before anything is written.
+        let mut request = RequestBuilder::<(), ()>::get("/containers/json").build()?;
+        request
+            .headers_mut()
+            .insert("X-Origin", "tests\r\n\r\nGET /secrets HTTP/1.1");
+        let mut buf = Vec::new();
+        let err = crate::Error::from(request.write_to(&mut buf).unwrap_err());
+        assert!(matches!(err.kind(), crate::ErrorKind::InvalidHeader));
+        assert!(buf.is_empty());
+
+        Ok(())
     }
 }
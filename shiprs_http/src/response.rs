@@ -1,10 +1,15 @@
-use std::collections::HashMap;
-use std::io::{BufReader, Error as IoError, ErrorKind, Read, Result as IoResult};
+use std::io::{BufRead, BufReader, Error as IoError, ErrorKind, Read};
 
 use crate::bytes::Bytes;
 use crate::error::{Error, HttpParsingKind::*, Result};
+use crate::header::{is_token, HeaderMap};
+use crate::io::{BodyReader, ReadBuffer};
+use crate::limits::Limits;
 use crate::version::HttpVersion;
-use crate::{CRLF, HEADERS_END};
+use crate::HEADERS_END;
+
+/// The size of the blocks in which chunked bodies are read.
+const BODY_BLOCK_SIZE: usize = 64 * 1024;
 
 /// An HTTP response.
 #[derive(Debug, Default, Clone)]
@@ -12,10 +17,10 @@ pub struct Response {
     #[allow(dead_code)]
     version: HttpVersion,
     status: u16,
-    #[allow(dead_code)]
     reason: String,
-    headers: HashMap<String, String>,
+    headers: HeaderMap,
     body: Vec<u8>,
+    trailers: HeaderMap,
 }
 
 impl Response {
@@ -23,37 +28,52 @@ impl Response {
         self.status
     }
 
-    pub fn headers(&self) -> &HashMap<String, String> {
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
         &self.headers
     }
 
     pub fn body(&self) -> &[u8] {
         &self.body
     }
+
+    /// The trailer fields sent after a `chunked` body.
+    ///
+    /// A response read with [`Response::stream`] has no trailers, they are given by the returned
+    /// [`BodyReader`] once the body has been read.
+    pub fn trailers(&self) -> &HeaderMap {
+        &self.trailers
+    }
 }
 
 pub struct Parser<R> {
-    inner: BufReader<R>,
+    inner: ReadBuffer<R>,
     kind: BodyKind,
+    limits: Limits,
+    trailers: HeaderMap,
 }
 
 impl<R> Parser<R>
 where
     R: Read,
 {
-    pub fn new(inner: R) -> Self {
+    pub fn new(inner: R, limits: &Limits) -> Self {
         Self {
-            inner: BufReader::new(inner),
+            inner: ReadBuffer::new(inner),
             kind: BodyKind::Empty,
+            limits: *limits,
+            trailers: HeaderMap::new(),
         }
     }
 
-    fn parse_until_headers(
-        &mut self,
-    ) -> Result<(HttpVersion, u16, String, HashMap<String, String>)> {
-        let buf = self.read_until_headers()?;
+    /// Parse the status line and the headers, in place in the read buffer.
+    fn parse_until_headers(&mut self) -> Result<(HttpVersion, u16, String, HeaderMap)> {
+        let end = self.read_until_headers()?;
 
-        let mut bytes = Bytes::new(&buf);
+        let mut bytes = Bytes::new(&self.inner.buffer()[..end]);
 
         let version = parse_version(&mut bytes)?;
         space!(bytes or Version.into());
@@ -73,10 +93,12 @@ where
         .to_string();
         bytes.commit();
 
-        let mut headers = HashMap::new();
-        parse_headers(&mut bytes, &mut headers)?;
+        let mut headers = HeaderMap::new();
+        parse_headers(&mut bytes, &mut headers, self.limits.max_headers)?;
         self.kind = BodyKind::try_from_headers(&headers)?;
 
+        self.inner.consume(end);
+
         Ok((version, status, reason, headers))
     }
 
@@ -88,61 +110,48 @@ where
         }
     }
 
-    fn read_until_headers(&mut self) -> IoResult<Vec<u8>> {
-        let mut buf = Vec::new();
+    /// Fill the read buffer until it holds the whole head of the response, and return the length
+    /// of the head. Only the newly read bytes are searched for the end of the head.
+    fn read_until_headers(&mut self) -> Result<usize> {
+        let max = self.limits.max_head_size;
+        let mut searched = 0;
 
         loop {
-            let byte = self.inner.by_ref().bytes().next();
-
-            match byte {
-                Some(b) => buf.push(b?),
-                None => return Err(IoError::new(ErrorKind::ConnectionAborted, "Unexpected EOF")),
-            };
-
-            if buf.ends_with(HEADERS_END) {
-                break;
-            }
-        }
-
-        Ok(buf)
-    }
-
-    fn read_next_line(&mut self) -> Result<Vec<u8>> {
-        let mut buf = Vec::new();
-
-        loop {
-            let byte = self.inner.by_ref().bytes().next();
-
-            match byte {
-                Some(b) => buf.push(b?),
-                None => {
-                    return Err(IoError::new(ErrorKind::ConnectionAborted, "Unexpected EOF").into())
+            let buf = self.inner.buffer();
+            if let Some(end) = buf[searched..]
+                .windows(HEADERS_END.len())
+                .position(|window| window == HEADERS_END)
+            {
+                let end = searched + end + HEADERS_END.len();
+                if end > max {
+                    return Err(HeadTooLarge.into());
                 }
-            };
+                return Ok(end);
+            }
+            if buf.len() >= max {
+                return Err(HeadTooLarge.into());
+            }
+            searched = buf.len().saturating_sub(HEADERS_END.len() - 1);
 
-            if buf.ends_with(CRLF) {
-                break;
+            if self.inner.fill_more()? == 0 {
+                return Err(IoError::new(ErrorKind::ConnectionAborted, "Unexpected EOF").into());
             }
         }
-
-        Ok(buf)
     }
 }
 
-enum BodyKind {
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum BodyKind {
     Chunked,
     Empty,
     Length(usize),
 }
 
 impl BodyKind {
-    fn try_from_headers(headers: &HashMap<String, String>) -> Result<Self> {
-        if headers.get("Transfer-Encoding").map(|h| h.as_str()) == Some("chunked") {
+    fn try_from_headers(headers: &HeaderMap) -> Result<Self> {
+        if headers.is_chunked() {
             Ok(BodyKind::Chunked)
-        } else if let Some(length_s) = headers.get("Content-Length") {
-            let length = length_s
-                .parse()
-                .map_err::<Error, _>(|_| ContentLength.into())?;
+        } else if let Some(length) = headers.content_length()? {
             Ok(BodyKind::Length(length))
         } else {
             Ok(BodyKind::Empty)
@@ -153,7 +162,7 @@ impl BodyKind {
 #[inline]
 fn parse_version(bytes: &mut Bytes) -> Result<HttpVersion> {
     if let Some(eight) = bytes.peek_n::<[u8; 8]>() {
-        let h10 = u64::from_ne_bytes(*b"HTTP/1.O");
+        let h10 = u64::from_ne_bytes(*b"HTTP/1.0");
         let h11 = u64::from_ne_bytes(*b"HTTP/1.1");
 
         // SAFETY: The bytes are guaranteed to be 8 bytes long.
@@ -231,80 +240,101 @@ fn parse_reason<'buf>(bytes: &mut Bytes<'buf>) -> Result<&'buf str> {
 }
 
 #[inline]
-fn parse_headers(bytes: &mut Bytes, map: &mut HashMap<String, String>) -> Result<usize> {
+pub(crate) fn parse_headers(
+    bytes: &mut Bytes,
+    map: &mut HeaderMap,
+    max_headers: usize,
+) -> Result<usize> {
     let start = bytes.as_ref().as_ptr() as usize;
 
     loop {
-        match next!(bytes => Err(Header.into())) {
-            b'\r' => {
+        // An empty line ends the headers.
+        match bytes.peek() {
+            Some(b'\r') => {
+                // SAFETY: we've peeked the next byte.
+                unsafe { bytes.bump() };
                 expect!(bytes.next() == b'\n' => Err(Header.into()));
                 return Ok(bytes.as_ref().as_ptr() as usize - start);
             }
-            b'\n' => return Ok(bytes.as_ref().as_ptr() as usize - start),
-            b':' => {
-                // SAFETY: at least one next! call has advanced the bytes iterator.
-                // It's just to remove the colon.
-                let name = unsafe { bytes.slice_skip(1) };
-
-                // Remove leading whitespace.
-                while bytes.peek() == Some(b' ') {
-                    // SAFETY: at least one we've peeked the next byte.
-                    unsafe { bytes.bump() };
-                }
-                bytes.commit();
-                loop {
-                    match next!(bytes => Err(Header.into())) {
-                        b'\r' => {
-                            expect!(bytes.next() == b'\n' => Err(Header.into()));
-                            break;
-                        }
-                        b'\n' => break,
-                        _ => continue,
-                    }
-                }
+            Some(b'\n') => {
+                // SAFETY: we've peeked the next byte.
+                unsafe { bytes.bump() };
+                return Ok(bytes.as_ref().as_ptr() as usize - start);
+            }
+            _ => bytes.commit(),
+        }
+
+        loop {
+            match next!(bytes => Err(Header.into())) {
+                b':' => break,
+                b if is_token(b) => continue,
+                _ => return Err(Header.into()),
+            }
+        }
+        // SAFETY: at least one next! call has advanced the bytes iterator.
+        // It's just to remove the colon.
+        let name = unsafe { bytes.slice_skip(1) };
+        if name.is_empty() {
+            return Err(Header.into());
+        }
+
+        // Remove leading whitespace.
+        while let Some(b' ' | b'\t') = bytes.peek() {
+            // SAFETY: we've peeked the next byte.
+            unsafe { bytes.bump() };
+        }
+        bytes.commit();
 
-                // SAFETY: at least two next! calls have advanced the bytes iterator.
-                let value = unsafe { bytes.slice_skip(2) };
-                map.insert(
-                    // SAFETY: the bytes returned by slice are valid US-ASCII chars.
-                    unsafe { std::str::from_utf8_unchecked(name) }.to_string(),
-                    // SAFETY: the bytes returned by slice are valid US-ASCII chars.
-                    unsafe { std::str::from_utf8_unchecked(value) }.to_string(),
-                );
+        let line_end = loop {
+            match next!(bytes => Err(Header.into())) {
+                b'\r' => {
+                    expect!(bytes.next() == b'\n' => Err(Header.into()));
+                    break 2;
+                }
+                b'\n' => break 1,
+                b'\t' | b' ' | 0x21..=0x7E | 0x80..=0xFF => continue,
+                _ => return Err(Header.into()),
             }
-            _ => continue,
+        };
+        // SAFETY: the line ending has been read by the next! calls above.
+        let value = unsafe { bytes.slice_skip(line_end) };
+
+        if map.len() >= max_headers {
+            return Err(TooManyHeaders.into());
         }
+        map.append(
+            // SAFETY: the name only holds token chars, which are US-ASCII.
+            unsafe { std::str::from_utf8_unchecked(name) },
+            std::str::from_utf8(value)
+                .map_err::<Error, _>(|_| Header.into())?
+                .trim_end_matches([' ', '\t']),
+        );
     }
 }
 
 #[inline]
 fn parse_chunked_body<R: Read>(parser: &mut Parser<R>) -> Result<Vec<u8>> {
-    let mut body = Vec::new();
+    let max = parser.limits.max_body_size;
+    let mut reader = BodyReader::new(&mut parser.inner, BodyKind::Chunked, &parser.limits);
 
+    // The body is read in blocks at least as large as the read buffer, so that large chunks are
+    // read straight into the returned buffer.
+    let mut body = Vec::new();
+    let mut len = 0;
     loop {
-        let chunk_length_b = parser.read_next_line()?;
-        let chunk_length_s = std::str::from_utf8(&chunk_length_b[..chunk_length_b.len() - 2])
-            .map_err::<Error, _>(|_| ChunkSize.into())?;
-        let chunk_length =
-            usize::from_str_radix(chunk_length_s, 16).map_err::<Error, _>(|_| ChunkSize.into())?;
-
-        if chunk_length == 0 {
-            // Read the last CRLF.
-            parser
-                .read_exact(&mut [0; 2])
-                .map_err::<Error, _>(|_| Chunk.into())?;
-            break;
+        if body.len() - len < BODY_BLOCK_SIZE {
+            body.resize(len + BODY_BLOCK_SIZE, 0);
+        }
+        match reader.read(&mut body[len..])? {
+            0 => break,
+            n => len += n,
+        }
+        if len > max {
+            return Err(BodyTooLarge.into());
         }
-
-        let mut chunk = vec![0; chunk_length];
-        parser
-            .read_exact(&mut chunk)
-            .map_err::<Error, _>(|_| Chunk.into())?;
-        body.extend(chunk);
-
-        // Read the CRLF.
-        parser.read_exact(&mut [0; 2])?;
     }
+    body.truncate(len);
+    parser.trailers = reader.trailers().clone();
 
     Ok(body)
 }
@@ -315,56 +345,107 @@ fn parse_length_body<R: Read>(parser: &mut Parser<R>) -> Result<Vec<u8>> {
         BodyKind::Length(length) => length,
         _ => unreachable!("parse_length_body called with non-length body kind"),
     };
+    if length > parser.limits.max_body_size {
+        return Err(BodyTooLarge.into());
+    }
 
-    let mut buf = vec![0; length];
-    parser.read_exact(&mut buf)?;
+    // The body is read in as few reads as possible, straight into the returned buffer.
+    let mut body = Vec::with_capacity(length);
+    (&mut parser.inner)
+        .take(length as u64)
+        .read_to_end(&mut body)?;
+    if body.len() != length {
+        return Err(IoError::new(ErrorKind::UnexpectedEof, "Unexpected EOF").into());
+    }
 
-    Ok(buf)
+    Ok(body)
 }
 
-impl<R> TryFrom<BufReader<R>> for Response
-where
-    R: Read,
-{
-    type Error = Error;
-
-    fn try_from(reader: BufReader<R>) -> Result<Self> {
-        let mut parser = Parser::new(reader);
+impl Response {
+    /// Parse the status line and headers from `reader`, leaving the body unread.
+    ///
+    /// The returned [`BodyReader`] reads the body straight from `reader`, which allows large
+    /// bodies to be processed without holding them in memory.
+    pub fn stream<R>(reader: R, limits: &Limits) -> Result<(Response, BodyReader<ReadBuffer<R>>)>
+    where
+        R: Read,
+    {
+        let mut parser = Parser::new(reader, limits);
         let (version, status, reason, headers) = parser.parse_until_headers()?;
-        let body = parser.parse_body()?;
+        if let 204 | 304 = status {
+            parser.kind = BodyKind::Empty;
+        }
 
-        let body = match (body, status) {
-            (Some(_), 204 | 304) => Vec::new(),
-            (Some(body), 200..=399) => body,
-            (Some(body), 400..=599) => body,
-            _ => Vec::new(),
+        let response = Response {
+            version,
+            status,
+            reason,
+            headers,
+            body: Vec::new(),
+            trailers: HeaderMap::new(),
         };
 
+        Ok((response, BodyReader::new(parser.inner, parser.kind, limits)))
+    }
+}
+
+impl Response {
+    /// Parse the response to a `HEAD` request from `reader`.
+    ///
+    /// The headers describe the body the matching `GET` request would have returned, but no body
+    /// is ever sent, so it is not read.
+    pub fn head<R>(reader: R, limits: &Limits) -> Result<Response>
+    where
+        R: Read,
+    {
+        let mut parser = Parser::new(reader, limits);
+        let (version, status, reason, headers) = parser.parse_until_headers()?;
+
         Ok(Response {
             version,
             status,
             reason,
             headers,
-            body,
+            body: Vec::new(),
+            trailers: HeaderMap::new(),
         })
     }
 }
 
-impl<R> From<R> for Parser<R>
-where
-    R: Read,
-{
-    fn from(inner: R) -> Self {
-        Parser::new(inner)
+impl Response {
+    /// Parse a whole response, including its body, from `reader`.
+    pub fn read_from<R>(reader: R, limits: &Limits) -> Result<Response>
+    where
+        R: Read,
+    {
+        let mut parser = Parser::new(reader, limits);
+        let (version, status, reason, headers) = parser.parse_until_headers()?;
+        // These responses never have a body, whatever their headers say. Reading one would take
+        // the bytes of the next response on the connection.
+        if let 100..=199 | 204 | 304 = status {
+            parser.kind = BodyKind::Empty;
+        }
+        let body = parser.parse_body()?.unwrap_or_default();
+
+        Ok(Response {
+            version,
+            status,
+            reason,
+            headers,
+            body,
+            trailers: parser.trailers,
+        })
     }
 }
 
-impl<R> Read for Parser<R>
+impl<R> TryFrom<BufReader<R>> for Response
 where
     R: Read,
 {
-    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
-        self.inner.read(buf)
+    type Error = Error;
+
+    fn try_from(reader: BufReader<R>) -> Result<Self> {
+        Response::read_from(reader, &Limits::default())
     }
 }
 
@@ -412,12 +493,12 @@ mod tests {
     fn test_parse_headers() -> Result<()> {
         let headers = b"Content-Type: text/plain\r\nContent-Length: 12\r\n\r\n";
         let mut headers = Bytes::new(headers);
-        let mut map = HashMap::new();
+        let mut map = HeaderMap::new();
 
-        let _ = parse_headers(&mut headers, &mut map)?;
+        let _ = parse_headers(&mut headers, &mut map, 100)?;
 
-        assert_eq!(map.get("Content-Type"), Some(&"text/plain".to_string()));
-        assert_eq!(map.get("Content-Length"), Some(&"12".to_string()));
+        assert_eq!(map.get("Content-Type"), Some("text/plain"));
+        assert_eq!(map.get("Content-Length"), Some("12"));
 
         Ok(())
     }
@@ -425,10 +506,22 @@ mod tests {
     #[test]
     fn test_parse_chunked_body() -> Result<()> {
         let bytes: &[u8] = b"4\r\nWiki\r\n7\r\npedia i\r\nB\r\nn \r\nchunks.\r\n0\r\n\r\n";
-        let mut parser = Parser {
-            inner: BufReader::new(bytes),
-            kind: BodyKind::Chunked,
-        };
+        let mut parser = Parser::new(bytes, &Limits::default());
+        parser.kind = BodyKind::Chunked;
+
+        let body = parse_chunked_body(&mut parser)?;
+
+        assert_eq!(body, b"Wikipedia in \r\nchunks.");
+        assert!(parser.trailers.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_chunked_body_with_extensions() -> Result<()> {
+        let bytes: &[u8] = b"4;ext\r\nWiki\r\n7 ; name=value\r\npedia i\r\nB;a=1;b=\"two\"\r\nn \r\nchunks.\r\n0\r\n\r\n";
+        let mut parser = Parser::new(bytes, &Limits::default());
+        parser.kind = BodyKind::Chunked;
 
         let body = parse_chunked_body(&mut parser)?;
 
@@ -437,13 +530,57 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_chunked_body_with_trailers() -> Result<()> {
+        let bytes: &[u8] = b"4\r\nWiki\r\n5\r\npedia\r\n0\r\nDigest: sha-256=abc\r\nwarning: first\r\nWarning: second\r\n\r\n";
+        let mut parser = Parser::new(bytes, &Limits::default());
+        parser.kind = BodyKind::Chunked;
+
+        let body = parse_chunked_body(&mut parser)?;
+
+        assert_eq!(body, b"Wikipedia");
+        assert_eq!(parser.trailers.get("digest"), Some("sha-256=abc"));
+        assert_eq!(
+            parser.trailers.get_all("Warning").collect::<Vec<_>>(),
+            vec!["first", "second"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_chunked_body_with_invalid_size() {
+        for bytes in [
+            &b"+4\r\nWiki\r\n0\r\n\r\n"[..],
+            b";ext\r\nWiki\r\n0\r\n\r\n",
+            b"4 4\r\nWiki\r\n0\r\n\r\n",
+            b"0\r\nNot a trailer\r\n\r\n",
+            b"0\r\nTrailer: without end\r\n",
+        ] {
+            let mut parser = Parser::new(bytes, &Limits::default());
+            parser.kind = BodyKind::Chunked;
+
+            assert!(parse_chunked_body(&mut parser).is_err());
+        }
+    }
+
+    #[test]
+    fn test_parse_response_with_trailers() -> Result<()> {
+        let response: &[u8] = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nTrailer: Expires\r\n\r\n4;ext=1\r\nWiki\r\n0\r\nExpires: never\r\n\r\n";
+        let response = Response::read_from(response, &Limits::default())?;
+
+        assert_eq!(response.body, b"Wiki");
+        assert_eq!(response.trailers().get("Expires"), Some("never"));
+        assert_eq!(response.headers.get("Trailer"), Some("Expires"));
+
+        Ok(())
+    }
+
     #[test]
     fn test_parse_length_body() -> Result<()> {
         let bytes: &[u8] = b"Hello, World!";
-        let mut parser = Parser {
-            inner: BufReader::new(bytes),
-            kind: BodyKind::Length(13),
-        };
+        let mut parser = Parser::new(bytes, &Limits::default());
+        parser.kind = BodyKind::Length(13);
 
         let body = parse_length_body(&mut parser)?;
 
@@ -460,14 +597,8 @@ mod tests {
         assert_eq!(response.version, HttpVersion::Http1_1);
         assert_eq!(response.status, 200);
         assert_eq!(response.reason, "OK");
-        assert_eq!(
-            response.headers.get("Content-Type"),
-            Some(&"text/plain".to_string())
-        );
-        assert_eq!(
-            response.headers.get("Transfer-Encoding"),
-            Some(&"chunked".to_string())
-        );
+        assert_eq!(response.headers.get("Content-Type"), Some("text/plain"));
+        assert_eq!(response.headers.get("Transfer-Encoding"), Some("chunked"));
         assert_eq!(response.body, b"\"Wikipedia in chunks.\"");
 
         Ok(())
@@ -481,15 +612,24 @@ mod tests {
         assert_eq!(response.version, HttpVersion::Http1_1);
         assert_eq!(response.status, 200);
         assert_eq!(response.reason, "OK");
+        assert_eq!(response.headers.get("Content-Type"), Some("text/plain"));
+        assert_eq!(response.headers.get("Content-Length"), Some("15"));
+        assert_eq!(response.body, b"\"Hello, World!\"");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_response_with_lowercase_headers() -> Result<()> {
+        let response: &[u8] = b"HTTP/1.1 200 OK\r\nwarning: first\r\ncontent-length: 5\r\nWarning: second\r\n\r\nHello";
+        let response = Response::try_from(BufReader::new(response))?;
+
+        assert_eq!(response.headers.get("Content-Length"), Some("5"));
         assert_eq!(
-            response.headers.get("Content-Type"),
-            Some(&"text/plain".to_string())
-        );
-        assert_eq!(
-            response.headers.get("Content-Length"),
-            Some(&"15".to_string())
+            response.headers.get_all("Warning").collect::<Vec<_>>(),
+            vec!["first", "second"]
         );
-        assert_eq!(response.body, b"\"Hello, World!\"");
+        assert_eq!(response.body, b"Hello");
 
         Ok(())
     }
@@ -503,16 +643,127 @@ mod tests {
         assert_eq!(response.version, HttpVersion::Http1_1);
         assert_eq!(response.status, 204);
         assert_eq!(response.reason, "No-Content");
+        assert_eq!(response.headers.get("Content-Type"), Some("none"));
+        assert_eq!(response.headers.get("Version"), Some("v1.44"));
+        assert!(response.body.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stream_response_with_chunked_body() -> Result<()> {
+        let response: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Type: application/x-tar\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n";
+        let (response, mut body) = Response::stream(response, &Limits::default())?;
+
+        assert_eq!(response.status, 200);
         assert_eq!(
             response.headers.get("Content-Type"),
-            Some(&"none".to_string())
+            Some("application/x-tar")
         );
-        assert_eq!(response.headers.get("Version"), Some(&"v1.44".to_string()));
         assert!(response.body.is_empty());
 
+        let mut buf = Vec::new();
+        body.read_to_end(&mut buf)?;
+        assert_eq!(buf, b"Wikipedia");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_head_response() -> Result<()> {
+        let response: &[u8] =
+            b"HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nContent-Length: 42\r\n\r\n";
+        let response = Response::head(response, &Limits::default())?;
+
+        assert_eq!(response.status, 404);
+        assert_eq!(response.reason, "Not Found");
+        assert!(response.body.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_response_with_large_head() -> Result<()> {
+        let value = "a".repeat(20 * 1024);
+        let response =
+            format!("HTTP/1.1 200 OK\r\nX-Large: {value}\r\nContent-Length: 5\r\n\r\nHello");
+        let response = Response::read_from(response.as_bytes(), &Limits::default())?;
+
+        assert_eq!(response.headers.get("X-Large"), Some(value.as_str()));
+        assert_eq!(response.body, b"Hello");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_response_read_in_small_pieces() -> Result<()> {
+        /// A reader returning at most 3 bytes at a time, like a slow socket.
+        struct Trickle<'a>(&'a [u8]);
+
+        impl Read for Trickle<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let n = buf.len().min(3).min(self.0.len());
+                buf[..n].copy_from_slice(&self.0[..n]);
+                self.0 = &self.0[n..];
+                Ok(n)
+            }
+        }
+
+        let response = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n";
+        let response = Response::read_from(Trickle(response), &Limits::default())?;
+
+        assert_eq!(response.headers.get("Transfer-Encoding"), Some("chunked"));
+        assert_eq!(response.body, b"Wikipedia");
+
         Ok(())
     }
 
+    #[test]
+    fn test_parse_responses_without_body() -> Result<()> {
+        /// A reader returning a single message at a time, like a socket the peer writes
+        /// responses to one after the other.
+        struct Messages<'a>(std::collections::VecDeque<&'a [u8]>);
+
+        impl Read for Messages<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let Some(message) = self.0.pop_front() else {
+                    return Ok(0);
+                };
+                buf[..message.len()].copy_from_slice(message);
+                Ok(message.len())
+            }
+        }
+
+        let mut socket = Messages(
+            [
+                &b"HTTP/1.1 304 Not Modified\r\nContent-Length: 12\r\n\r\n"[..],
+                &b"HTTP/1.1 204 No Content\r\nContent-Length: 12\r\n\r\n"[..],
+                &b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nHello"[..],
+            ]
+            .into(),
+        );
+
+        let response = Response::read_from(&mut socket, &Limits::default())?;
+        assert_eq!(response.status, 304);
+        assert!(response.body.is_empty());
+
+        let response = Response::read_from(&mut socket, &Limits::default())?;
+        assert_eq!(response.status, 204);
+        assert!(response.body.is_empty());
+
+        let response = Response::read_from(&mut socket, &Limits::default())?;
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, b"Hello");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_truncated_length_body() {
+        let response: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Length: 15\r\n\r\nHello";
+        assert!(Response::read_from(response, &Limits::default()).is_err());
+    }
+
     #[test]
     fn test_convert_response() {
         let response: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 15\r\n\r\n\"Hello, World!\"";
@@ -521,14 +772,144 @@ mod tests {
         assert_eq!(response.version, HttpVersion::Http1_1);
         assert_eq!(response.status, 200);
         assert_eq!(response.reason, "OK");
-        assert_eq!(
-            response.headers.get("Content-Type"),
-            Some(&"text/plain".to_string())
-        );
-        assert_eq!(
-            response.headers.get("Content-Length"),
-            Some(&"15".to_string())
-        );
+        assert_eq!(response.headers.get("Content-Type"), Some("text/plain"));
+        assert_eq!(response.headers.get("Content-Length"), Some("15"));
         assert_eq!(response.body, b"\"Hello, World!\"");
     }
+
+    fn parsing_kind(err: Error) -> crate::error::HttpParsingKind {
+        match err.kind() {
+            crate::error::ErrorKind::HttpParsing(kind) => *kind,
+            kind => panic!("not a parsing error: {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn test_parse_response_over_limits() {
+        let limits = Limits {
+            max_head_size: 64,
+            max_headers: 2,
+            max_chunk_size: 4,
+            max_body_size: 8,
+        };
+
+        let response = format!("HTTP/1.1 200 OK\r\nX-Large: {}\r\n\r\n", "a".repeat(64));
+        let err = Response::read_from(response.as_bytes(), &limits).unwrap_err();
+        assert_eq!(parsing_kind(err), HeadTooLarge);
+
+        // The end of the head is never sent.
+        let response = format!("HTTP/1.1 200 OK\r\nX-Large: {}", "a".repeat(1024 * 1024));
+        let err = Response::read_from(response.as_bytes(), &limits).unwrap_err();
+        assert_eq!(parsing_kind(err), HeadTooLarge);
+
+        let response: &[u8] = b"HTTP/1.1 200 OK\r\nA: 1\r\nB: 2\r\nC: 3\r\n\r\n";
+        let err = Response::read_from(response, &limits).unwrap_err();
+        assert_eq!(parsing_kind(err), TooManyHeaders);
+
+        let response: &[u8] =
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nWikip\r\n0\r\n\r\n";
+        let err = Response::read_from(response, &limits).unwrap_err();
+        assert_eq!(parsing_kind(err), ChunkTooLarge);
+
+        let response: &[u8] = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nWiki\r\n4\r\npedi\r\n1\r\na\r\n0\r\n\r\n";
+        let err = Response::read_from(response, &limits).unwrap_err();
+        assert_eq!(parsing_kind(err), BodyTooLarge);
+
+        // The body is refused before anything is allocated for it.
+        let response: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Length: 18446744073709551615\r\n\r\n";
+        let err = Response::read_from(response, &limits).unwrap_err();
+        assert_eq!(parsing_kind(err), BodyTooLarge);
+    }
+
+    #[test]
+    fn test_parse_invalid_headers() {
+        for response in [
+            &b"HTTP/1.1 200 OK\r\nNo colon\r\n\r\n"[..],
+            b"HTTP/1.1 200 OK\r\n: no name\r\n\r\n",
+            b"HTTP/1.1 200 OK\r\nNull: \0\r\n\r\n",
+            b"HTTP/1.1 200 OK\r\nLatin-1: \xe9t\xe9\r\n\r\n",
+        ] {
+            let err = Response::read_from(response, &Limits::default()).unwrap_err();
+            assert_eq!(parsing_kind(err), Header);
+        }
+    }
+
+    /// Encode `body` with the `chunked` transfer coding, cutting it at `cuts`.
+    fn chunked(body: &[u8], cuts: &[usize]) -> Vec<u8> {
+        let mut cuts = cuts
+            .iter()
+            .map(|cut| cut % (body.len() + 1))
+            .collect::<Vec<_>>();
+        cuts.extend([0, body.len()]);
+        cuts.sort_unstable();
+        cuts.dedup();
+
+        let mut encoded = Vec::new();
+        for window in cuts.windows(2) {
+            let chunk = &body[window[0]..window[1]];
+            encoded.extend_from_slice(format!("{:x}\r\n", chunk.len()).as_bytes());
+            encoded.extend_from_slice(chunk);
+            encoded.extend_from_slice(b"\r\n");
+        }
+        encoded.extend_from_slice(b"0\r\n\r\n");
+        encoded
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_parse_arbitrary_bytes(bytes in proptest::collection::vec(proptest::num::u8::ANY, 0..512)) {
+            let _ = Response::read_from(&bytes[..], &Limits::default());
+        }
+
+        #[test]
+        fn prop_parse_arbitrary_head(
+            head in "HTTP/1\\.[01] [0-9]{3}[ -~]{0,16}\r\n([!-~]{0,8}:[ -~]{0,16}\r?\n){0,8}\r\n",
+            body in proptest::collection::vec(proptest::num::u8::ANY, 0..64),
+        ) {
+            let mut response = head.into_bytes();
+            response.extend_from_slice(&body);
+            let _ = Response::read_from(&response[..], &Limits::default());
+        }
+
+        #[test]
+        fn prop_parse_chunked_body(
+            body in proptest::collection::vec(proptest::num::u8::ANY, 0..4096),
+            cuts in proptest::collection::vec(0..4096usize, 0..16),
+        ) {
+            let mut response = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n".to_vec();
+            response.extend_from_slice(&chunked(&body, &cuts));
+
+            let response = Response::read_from(&response[..], &Limits::default()).unwrap();
+            proptest::prop_assert_eq!(response.body, body);
+        }
+
+        #[test]
+        fn prop_parse_truncated_response(
+            body in proptest::collection::vec(proptest::num::u8::ANY, 1..256),
+            cuts in proptest::collection::vec(0..256usize, 0..8),
+            truncate in 0..1024usize,
+        ) {
+            let mut response = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n".to_vec();
+            response.extend_from_slice(&chunked(&body, &cuts));
+            let truncate = truncate % response.len();
+
+            proptest::prop_assert!(Response::read_from(&response[..truncate], &Limits::default()).is_err());
+        }
+
+        #[test]
+        fn prop_parse_respects_limits(
+            length in 0..u64::MAX,
+            max_body_size in 0..1024usize,
+        ) {
+            let response = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", length);
+            let limits = Limits { max_body_size, ..Default::default() };
+
+            let result = Response::read_from(response.as_bytes(), &limits);
+            if length > max_body_size as u64 {
+                proptest::prop_assert_eq!(parsing_kind(result.unwrap_err()), BodyTooLarge);
+            } else if length > 0 {
+                proptest::prop_assert!(result.is_err());
+            }
+        }
+    }
 }
@@ -1,8 +1,9 @@
 use std::borrow::Cow;
+use std::ops::Deref;
 
 use serde::Serialize;
 
-use crate::error::Result;
+use crate::error::{Error, ErrorKind, Result};
 
 #[derive(Debug, Clone)]
 pub struct Uri<'a> {
@@ -22,6 +23,10 @@ impl<'a> Uri<'a> {
         }
         Ok(Uri { encoded: uri })
     }
+
+    pub(crate) fn prefix(&mut self, prefix: &str) {
+        self.encoded = Cow::Owned(format!("{}{}", prefix, self.encoded));
+    }
 }
 
 impl<'a> AsRef<str> for Uri<'a> {
@@ -30,6 +35,204 @@ impl<'a> AsRef<str> for Uri<'a> {
     }
 }
 
+/// Builder of a request path from a template, where each `{}` is replaced by a segment, such as
+/// the ID or the name of an object.
+///
+/// Segments are percent-encoded, so that names holding a `/`, `?` or `#` can't change the
+/// endpoint the request is sent to.
+///
+/// # Example
+/// ```
+/// use shiprs_http::uri::PathTemplate;
+///
+/// let path = PathTemplate::new("/distribution/{}/json")
+///     .segment("localhost:5000/androw/uhttpd")
+///     .build()
+///     .unwrap();
+/// assert_eq!(path, "/distribution/localhost:5000%2Fandrow%2Fuhttpd/json");
+/// ```
+#[derive(Debug, Clone)]
+pub struct PathTemplate<'t> {
+    path: String,
+    template: &'t str,
+    rest: &'t str,
+    error: Option<String>,
+}
+
+impl<'t> PathTemplate<'t> {
+    pub fn new(template: &'t str) -> Self {
+        PathTemplate {
+            path: String::with_capacity(template.len() + 64),
+            template,
+            rest: template,
+            error: None,
+        }
+    }
+
+    /// Replace the next `{}` of the template with `segment`.
+    ///
+    /// # Panics
+    /// Panics if there is no `{}` left in the template.
+    pub fn segment<S: AsRef<str>>(mut self, segment: S) -> Self {
+        let segment = segment.as_ref();
+        let (before, after) = self
+            .rest
+            .split_once("{}")
+            .expect("no placeholder left in the path template");
+        self.path.push_str(before);
+        self.rest = after;
+
+        // Empty and dot segments would be removed when the daemon normalizes the path,
+        // e.g. `/containers/../json` would become `/json`.
+        if let ("" | "." | "..", None) = (segment, &self.error) {
+            self.error = Some(format!("{:?} is not a valid path segment", segment));
+        }
+        encode_segment(segment, &mut self.path);
+
+        self
+    }
+
+    /// Build the path.
+    ///
+    /// Fails if one of the segments is empty, `.` or `..`.
+    ///
+    /// # Panics
+    /// Panics if a `{}` of the template has not been replaced.
+    pub fn build(mut self) -> Result<Path<'t>> {
+        assert!(
+            !self.rest.contains("{}"),
+            "placeholder left in the path template"
+        );
+        if let Some(error) = self.error {
+            return Err(Error::new(ErrorKind::InvalidPath).with(error));
+        }
+
+        self.path.push_str(self.rest);
+        Ok(Path {
+            path: self.path,
+            template: self.template,
+        })
+    }
+}
+
+/// A request path built by a [`PathTemplate`], which remembers the template it was built from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<'t> {
+    path: String,
+    template: &'t str,
+}
+
+impl<'t> Path<'t> {
+    pub fn as_str(&self) -> &str {
+        &self.path
+    }
+
+    /// The template the path was built from, e.g. `/containers/{}/json`.
+    pub fn template(&self) -> &'t str {
+        self.template
+    }
+}
+
+impl Deref for Path<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.path
+    }
+}
+
+impl PartialEq<str> for Path<'_> {
+    fn eq(&self, other: &str) -> bool {
+        self.path == other
+    }
+}
+
+impl PartialEq<&str> for Path<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self.path == *other
+    }
+}
+
+impl std::fmt::Display for Path<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.path)
+    }
+}
+
+/// The path a request is sent to, along with the endpoint it belongs to.
+///
+/// The endpoint of a [`Path`] is its template, so that requests on different objects are
+/// grouped together, e.g. by metrics. The endpoint of any other path is the path itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Endpoint<'a> {
+    path: &'a str,
+    template: &'a str,
+}
+
+impl<'a> Endpoint<'a> {
+    pub fn path(&self) -> &'a str {
+        self.path
+    }
+
+    pub fn template(&self) -> &'a str {
+        self.template
+    }
+}
+
+impl<'a> From<&'a str> for Endpoint<'a> {
+    fn from(path: &'a str) -> Self {
+        Endpoint {
+            path,
+            template: path,
+        }
+    }
+}
+
+impl<'a, 't: 'a> From<&'a Path<'t>> for Endpoint<'a> {
+    fn from(path: &'a Path<'t>) -> Self {
+        Endpoint {
+            path: &path.path,
+            template: path.template,
+        }
+    }
+}
+
+/// Append `segment` to `path`, percent-encoding every byte that is not allowed in a path segment
+/// by RFC 3986, as well as `%` itself.
+fn encode_segment(segment: &str, path: &mut String) {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+    for &b in segment.as_bytes() {
+        match b {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'.'
+            | b'_'
+            | b'~'
+            | b'!'
+            | b'$'
+            | b'&'
+            | b'\''
+            | b'('
+            | b')'
+            | b'*'
+            | b'+'
+            | b','
+            | b';'
+            | b'='
+            | b':'
+            | b'@' => path.push(b as char),
+            _ => {
+                path.push('%');
+                path.push(HEX[(b >> 4) as usize] as char);
+                path.push(HEX[(b & 0xF) as usize] as char);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -42,4 +245,56 @@ mod tests {
         let uri = Uri::parse("/containers/json", Some(vec![("all", "true")])).unwrap();
         assert_eq!(uri.as_ref(), "/containers/json?all=true");
     }
+
+    #[test]
+    fn path_template() -> Result<()> {
+        let path = PathTemplate::new("/containers/{}/json")
+            .segment("4fa6e0f0c678")
+            .build()?;
+        assert_eq!(path, "/containers/4fa6e0f0c678/json");
+
+        let path = PathTemplate::new("/plugins/{}/enable")
+            .segment("vieux/sshfs:latest")
+            .build()?;
+        assert_eq!(path, "/plugins/vieux%2Fsshfs:latest/enable");
+
+        let path = PathTemplate::new("/containers/{}")
+            .segment("web?force=true#top 100%")
+            .build()?;
+        assert_eq!(path, "/containers/web%3Fforce=true%23top%20100%25");
+
+        let path = PathTemplate::new("/{}/{}")
+            .segment("a")
+            .segment("é")
+            .build()?;
+        assert_eq!(path, "/a/%C3%A9");
+        assert_eq!(path.template(), "/{}/{}");
+
+        let endpoint = Endpoint::from(&path);
+        assert_eq!(endpoint.path(), "/a/%C3%A9");
+        assert_eq!(endpoint.template(), "/{}/{}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn path_template_refuses_dot_segments() {
+        for segment in ["", ".", ".."] {
+            let path = PathTemplate::new("/containers/{}/json")
+                .segment(segment)
+                .build();
+            assert!(path.is_err(), "{:?} was accepted", segment);
+        }
+
+        let path = PathTemplate::new("/containers/{}/json")
+            .segment("...")
+            .build();
+        assert_eq!(path.unwrap(), "/containers/.../json");
+    }
+
+    #[test]
+    #[should_panic(expected = "no placeholder left")]
+    fn path_template_with_too_many_segments() {
+        let _ = PathTemplate::new("/containers/json").segment("web");
+    }
 }
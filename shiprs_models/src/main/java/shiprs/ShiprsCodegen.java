@@ -59,7 +59,12 @@ public class ShiprsCodegen extends RustServerCodegen {
     private static ArrayList<String> upperCaseModelFields;
     static {
         upperCaseModelFields = new ArrayList();
-        upperCaseModelFields.add("IdResponse");
+    }
+
+    private static ArrayList<String> upperCaseAliasModelFields;
+    static {
+        upperCaseAliasModelFields = new ArrayList();
+        upperCaseAliasModelFields.add("IdResponse");
     }
 
     @Override
@@ -155,6 +160,10 @@ public class ShiprsCodegen extends RustServerCodegen {
                     prop.vendorExtensions.put("x-rustgen-upper-case", true);
                 }
 
+                if (upperCaseAliasModelFields.contains(model.classname)) {
+                    prop.vendorExtensions.put("x-rustgen-upper-case-alias", prop.baseName.toUpperCase());
+                }
+
                 if (prop.dataFormat != null && (prop.dataFormat.equals("dateTime") || prop.datatype.equals("ShiprsDate"))) {
                     // set DateTime format on properties where appropriate
                     prop.vendorExtensions.put("x-rustgen-is-datetime", true);
@@ -1,5 +1,5 @@
 use shiprs::{
-    container::{ListOption, RenameOption},
+    container::{CommitOption, ListOption, RenameOption},
     error::Result,
     Docker,
 };
@@ -189,3 +189,28 @@ fn integration_test_unpause_container() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn integration_test_commit_container() -> Result<()> {
+    let docker = Docker::new()?;
+
+    create_daemon(&docker, "integration_test_commit_container")?;
+
+    let options = CommitOption {
+        repo: Some("integration_test_commit_container"),
+        tag: Some("latest"),
+        changes: vec!["ENV SHIPRS=commit"],
+        ..Default::default()
+    };
+
+    let image = docker
+        .containers()
+        .get("integration_test_commit_container")
+        .commit(options, None)?;
+
+    assert!(image.id.starts_with("sha256:"));
+
+    remove_daemon(&docker, "integration_test_commit_container")?;
+
+    Ok(())
+}
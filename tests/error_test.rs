@@ -1,5 +1,4 @@
 use shiprs::{error::Result, Docker};
-use shiprs_models::models::ErrorResponse;
 
 pub mod common;
 use common::*;
@@ -9,16 +8,13 @@ fn integration_test_error_parsing() -> Result<()> {
     let docker = Docker::new()?;
     let image = format!("{}unknown_image:unkown", registry_http_addr());
 
-    let res = create_container(&docker, &image, "integration_test_error_parsing")?;
+    let err = create_container(&docker, &image, "integration_test_error_parsing").unwrap_err();
 
-    assert!(res.is_error());
-
-    let err_s = res.error().unwrap().body()?;
-    let expected_err = ErrorResponse {
-        message: "No such image: localhost:5000/unknown_image:unkown".to_string(),
-    };
-
-    assert_eq!(err_s, expected_err);
+    assert!(err.is_not_found());
+    assert_eq!(
+        err.to_string(),
+        "No such image: localhost:5000/unknown_image:unkown"
+    );
 
     Ok(())
 }
@@ -0,0 +1,24 @@
+use std::io::Read;
+
+use shiprs::{error::Result, Docker};
+
+mod common;
+use common::*;
+
+#[test]
+fn integration_test_export_import_images() -> Result<()> {
+    let docker = Docker::new()?;
+
+    let image = format!("{}hello-world:linux", registry_http_addr());
+
+    let mut tarball = Vec::new();
+    docker.images().export(&[&image])?.read_to_end(&mut tarball)?;
+
+    assert!(!tarball.is_empty());
+
+    let loaded = docker.images().import(&tarball[..], true)?;
+
+    assert_eq!(loaded, vec![image]);
+
+    Ok(())
+}
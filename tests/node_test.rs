@@ -0,0 +1,45 @@
+use shiprs::node::{ListFilter, ListOption, UpdateOption};
+use shiprs::{error::Result, Docker};
+use shiprs_models::models::{NodeSpecAvailabilityEnum, NodeSpecRoleEnum};
+
+// The node is the manager of the swarm initialized by `run_integration_tests.sh`.
+#[test]
+fn integration_test_nodes_and_tasks() -> Result<()> {
+    let docker = Docker::new()?;
+
+    let options = ListOption {
+        filters: vec![ListFilter::<String>::Role(NodeSpecRoleEnum::MANAGER)],
+    };
+    let managers = docker.nodes().list(Some(options))?;
+    assert_eq!(managers.len(), 1);
+    let node_id = managers[0].id.clone().unwrap();
+
+    let node = docker.nodes().get(&node_id);
+    let current = node.inspect()?;
+    let mut spec = current.spec.unwrap_or_default();
+    let availability = spec.availability;
+    spec.availability = Some(NodeSpecAvailabilityEnum::DRAIN);
+    let options = UpdateOption {
+        version: current.version.and_then(|v| v.index).unwrap(),
+    };
+    node.update(spec.clone(), options)?;
+
+    let drained = node.inspect()?;
+    assert_eq!(
+        drained.spec.and_then(|spec| spec.availability),
+        Some(NodeSpecAvailabilityEnum::DRAIN)
+    );
+
+    // Make the node available again to the services of the other tests.
+    spec.availability = availability;
+    let options = UpdateOption {
+        version: drained.version.and_then(|v| v.index).unwrap(),
+    };
+    node.update(spec, options)?;
+
+    for task in docker.tasks().list::<String>(None)? {
+        assert!(task.id.is_some());
+    }
+
+    Ok(())
+}
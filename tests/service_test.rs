@@ -0,0 +1,58 @@
+use shiprs::service::{LogsOption, UpdateOption};
+use shiprs::{error::Result, Docker};
+use shiprs_models::models::{ServiceSpec, TaskSpec, TaskSpecContainerSpec};
+
+mod common;
+use common::*;
+
+// Services are created in the swarm initialized by `run_integration_tests.sh`.
+#[test]
+fn integration_test_service_lifecycle() -> Result<()> {
+    let docker = Docker::new()?;
+
+    let spec = ServiceSpec {
+        name: Some("integration_test_service".to_string()),
+        task_template: Some(TaskSpec {
+            container_spec: Some(TaskSpecContainerSpec {
+                image: Some(format!("{}hello-world:linux", registry_http_addr())),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let created = docker.services().create(spec, None)?;
+    let id = created.id.unwrap();
+
+    let services = docker.services().list::<String>(None)?;
+    assert!(services.iter().any(|service| service.id.as_ref() == Some(&id)));
+
+    let service = docker.services().get(&id);
+    let current = service.inspect(None)?;
+    let mut spec = current.spec.unwrap_or_default();
+    spec.labels = Some([("shiprs".to_string(), "test".to_string())].into());
+    let options = UpdateOption {
+        version: current.version.and_then(|v| v.index).unwrap_or_default(),
+        ..Default::default()
+    };
+    service.update(spec, options, None)?;
+
+    let updated = service.inspect(None)?;
+    assert_eq!(
+        updated.spec.and_then(|spec| spec.labels).unwrap()["shiprs"],
+        "test"
+    );
+
+    let options = LogsOption {
+        stdout: true,
+        stderr: true,
+        ..Default::default()
+    };
+    for output in service.logs(Some(options))? {
+        output?;
+    }
+
+    service.remove()?;
+
+    Ok(())
+}
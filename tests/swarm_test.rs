@@ -0,0 +1,38 @@
+use shiprs::swarm::UpdateOption;
+use shiprs::{error::Result, Docker};
+use shiprs_models::models::SwarmInitRequest;
+
+// The daemon is already part of the swarm created by `run_integration_tests.sh`,
+// which the other tests rely on, so it must not be left.
+#[test]
+fn integration_test_swarm_lifecycle() -> Result<()> {
+    let docker = Docker::new()?;
+
+    let request = SwarmInitRequest {
+        listen_addr: Some("127.0.0.1:2377".to_string()),
+        advertise_addr: Some("127.0.0.1".to_string()),
+        ..Default::default()
+    };
+    let err = docker.swarm().init(request).unwrap_err();
+    assert_eq!(err.status(), Some(503));
+
+    let swarm = docker.swarm().inspect()?;
+    let worker_token = swarm.join_tokens.and_then(|tokens| tokens.worker);
+    assert!(worker_token.is_some());
+
+    let options = UpdateOption {
+        version: swarm.version.and_then(|v| v.index).unwrap_or_default(),
+        rotate_worker_token: true,
+        ..Default::default()
+    };
+    docker
+        .swarm()
+        .update(swarm.spec.unwrap_or_default(), options)?;
+
+    let swarm = docker.swarm().inspect()?;
+    assert_ne!(swarm.join_tokens.and_then(|tokens| tokens.worker), worker_token);
+
+    docker.swarm().unlock_key()?;
+
+    Ok(())
+}